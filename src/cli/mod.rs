@@ -1,7 +1,8 @@
 use clap::Parser;
 use anyhow::Result;
 use std::path::PathBuf;
-use crate::core::{ExtractorEngine, Downloader};
+use std::sync::Arc;
+use crate::core::{Downloader, ExtractionResult, ExtractorEngine, VideoMetadata};
 use crate::extractors::YouTubeExtractor;
 use crate::utils::generate_output_filename;
 
@@ -10,10 +11,10 @@ use crate::utils::generate_output_filename;
 #[command(about = "Modern video downloader with enhanced performance")]
 #[command(version)]
 pub struct Cli {
-    /// URL to download
-    #[arg(value_name = "URL")]
-    pub url: String,
-    
+    /// URL to download. Not required when `--serve` is passed.
+    #[arg(value_name = "URL", required_unless_present = "serve")]
+    pub url: Option<String>,
+
     /// Output directory
     #[arg(short, long, default_value = ".")]
     pub output: String,
@@ -33,26 +34,199 @@ pub struct Cli {
     /// Number of concurrent downloads
     #[arg(short = 'j', long, default_value = "1")]
     pub concurrent: usize,
+
+    /// Playlist entry to start at (1-based, inclusive)
+    #[arg(long, default_value = "1")]
+    pub playlist_start: usize,
+
+    /// Playlist entry to stop at (1-based, inclusive)
+    #[arg(long)]
+    pub playlist_end: Option<usize>,
+
+    /// Path to the ffmpeg binary or its containing directory, for muxing
+    /// separate video+audio (DASH) streams. Falls back to a PATH search.
+    #[arg(long)]
+    pub ffmpeg_location: Option<String>,
+
+    /// Maximum download rate, e.g. `500K` or `4.2M`
+    #[arg(long)]
+    pub rate_limit: Option<String>,
+
+    /// Abort and retry a connection whose throughput drops below this rate
+    /// for a sustained window, e.g. `100K`
+    #[arg(long)]
+    pub throttled_rate: Option<String>,
+
+    /// Route all requests through this proxy URL (e.g. `socks5://127.0.0.1:1080`).
+    /// Pass an empty string to force a direct connection.
+    #[arg(long)]
+    pub proxy: Option<String>,
+
+    /// Override the default User-Agent sent with every request
+    #[arg(long)]
+    pub user_agent: Option<String>,
+
+    /// Override the default Referer sent with extraction requests
+    #[arg(long)]
+    pub referer: Option<String>,
+
+    /// Extra header to send with every request, as `KEY:VALUE`. May be repeated.
+    #[arg(long = "add-header", value_name = "KEY:VALUE")]
+    pub add_header: Vec<String>,
+
+    /// Run as a resident signature-decryption daemon instead of downloading
+    /// `URL`, answering requests over a TCP socket until killed.
+    #[arg(long)]
+    pub serve: bool,
+
+    /// Address the signature-decryption daemon binds to, when `--serve` is passed.
+    #[arg(long, default_value = crate::server::DEFAULT_ADDR)]
+    pub serve_addr: String,
+
+    /// Download only the single video, even if the URL also points into a playlist
+    #[arg(long)]
+    pub no_playlist: bool,
+
+    /// Overall per-request HTTP timeout, in seconds
+    #[arg(long, default_value = "30")]
+    pub timeout: u64,
+
+    /// Timeout for establishing the TCP/TLS connection, in seconds. Defaults
+    /// to reqwest's built-in connect timeout when unset.
+    #[arg(long)]
+    pub connect_timeout: Option<u64>,
+
+    /// TLS backend for the HTTP client, e.g. rustls to avoid linking OpenSSL
+    #[arg(long, value_enum, default_value_t = TlsBackendArg::Default)]
+    pub tls_backend: TlsBackendArg,
+}
+
+/// CLI-facing mirror of `core::TlsBackend`, kept separate so `core` doesn't
+/// need to depend on clap's `ValueEnum` derive.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default)]
+pub enum TlsBackendArg {
+    #[default]
+    Default,
+    NativeTls,
+    RustlsWebpkiRoots,
+    RustlsNativeRoots,
+}
+
+impl From<TlsBackendArg> for crate::core::TlsBackend {
+    fn from(arg: TlsBackendArg) -> Self {
+        match arg {
+            TlsBackendArg::Default => crate::core::TlsBackend::Default,
+            TlsBackendArg::NativeTls => crate::core::TlsBackend::NativeTls,
+            TlsBackendArg::RustlsWebpkiRoots => crate::core::TlsBackend::RustlsWebpkiRoots,
+            TlsBackendArg::RustlsNativeRoots => crate::core::TlsBackend::RustlsNativeRoots,
+        }
+    }
 }
 
 impl Cli {
+    /// Parse `--add-header KEY:VALUE` flags into `(key, value)` pairs.
+    fn parsed_headers(&self) -> Result<Vec<(String, String)>> {
+        self.add_header
+            .iter()
+            .map(|header| {
+                let (key, value) = header
+                    .split_once(':')
+                    .ok_or_else(|| anyhow::anyhow!("Invalid header '{}', expected KEY:VALUE", header))?;
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            })
+            .collect()
+    }
+
+    /// Parses a `--rate-limit`/`--throttled-rate`-style flag value, e.g.
+    /// `500K`. Returns `Ok(None)` when the flag wasn't passed, but errors on
+    /// a value that was passed and failed to parse, so a typo like `5ooK`
+    /// can't silently collapse to "no limit" instead of being rejected.
+    fn parse_rate_flag(&self, flag_name: &str, value: Option<&str>) -> Result<Option<u64>> {
+        value
+            .map(|s| {
+                crate::core::rate_limiter::parse_rate(s)
+                    .ok_or_else(|| anyhow::anyhow!("Invalid {} value '{}'", flag_name, s))
+            })
+            .transpose()
+    }
+
+    /// Builds a `YouTubeExtractor` configured from the CLI flags shared by
+    /// extraction and (re-)resolution: proxy, user agent, referer, headers.
+    fn build_youtube_extractor(&self) -> Result<YouTubeExtractor> {
+        Ok(YouTubeExtractor::new()
+            .with_proxy(self.proxy.clone())?
+            .with_user_agent(self.user_agent.clone())?
+            .with_referer(self.referer.clone())
+            .with_headers(self.parsed_headers()?)
+            .with_no_playlist(self.no_playlist))
+    }
+
     pub async fn run(&self) -> Result<()> {
         if self.verbose {
             println!("Verbose mode enabled");
         }
-        
-        println!("Downloading: {}", self.url);
+
+        if self.serve {
+            let addr = self.serve_addr.parse().map_err(|e| {
+                anyhow::anyhow!("Invalid --serve-addr '{}': {}", self.serve_addr, e)
+            })?;
+            return crate::server::run(addr).await;
+        }
+
+        let url = self.url.as_deref().ok_or_else(|| anyhow::anyhow!("URL is required"))?;
+
+        println!("Downloading: {}", url);
         println!("Output directory: {}", self.output);
         println!("Format: {}", self.format);
-        
+
         // Initialize extractor engine
         let mut extractor_engine = ExtractorEngine::new();
-        extractor_engine.register_extractor(Box::new(YouTubeExtractor::new()));
-        
+        extractor_engine.register_extractor(Box::new(self.build_youtube_extractor()?));
+
         // Extract video metadata
         println!("Extracting video information...");
-        let metadata = extractor_engine.extract(&self.url).await?;
-        
+        match extractor_engine.extract(url).await? {
+            ExtractionResult::SingleVideo(metadata) => {
+                self.download_one(&metadata, None).await?;
+            }
+            ExtractionResult::Playlist(playlist) => {
+                println!(
+                    "Playlist: {} ({} entries)",
+                    playlist.title,
+                    playlist.entries.len()
+                );
+
+                let start = self.playlist_start.max(1);
+                let end = self.playlist_end.unwrap_or(playlist.entries.len());
+
+                for (i, entry) in playlist.entries.iter().enumerate() {
+                    let index = i + 1;
+                    if index < start || index > end {
+                        continue;
+                    }
+
+                    // Playlist entries are populated lazily with just an id;
+                    // re-extract full metadata before downloading each one.
+                    let video_url = format!("https://www.youtube.com/watch?v={}", entry.id);
+                    let metadata = match extractor_engine.extract(&video_url).await? {
+                        ExtractionResult::SingleVideo(metadata) => metadata,
+                        ExtractionResult::Playlist(_) => {
+                            println!("Skipping nested playlist entry: {}", entry.id);
+                            continue;
+                        }
+                    };
+
+                    self.download_one(&metadata, Some(index)).await?;
+                }
+            }
+        }
+
+        println!("Download completed!");
+
+        Ok(())
+    }
+
+    async fn download_one(&self, metadata: &VideoMetadata, playlist_index: Option<usize>) -> Result<()> {
         println!("Title: {}", metadata.title);
         if let Some(uploader) = &metadata.uploader {
             println!("Uploader: {}", uploader);
@@ -63,35 +237,53 @@ impl Cli {
         if let Some(view_count) = metadata.view_count {
             println!("Views: {}", view_count);
         }
-        
+
         println!("Available formats: {}", metadata.formats.len());
         for (i, format) in metadata.formats.iter().enumerate().take(5) {
-            println!("  {}: {} - {} ({})", 
-                i + 1, 
-                format.format_id, 
+            println!("  {}: {} - {} ({})",
+                i + 1,
+                format.format_id,
                 format.resolution.as_deref().unwrap_or("unknown"),
                 format.ext
             );
         }
-        
+
         // Generate output filename
         let template = self.output_template
             .as_deref()
             .unwrap_or("%(title)s.%(ext)s");
-        let filename = generate_output_filename(template, &metadata);
+        let filename = generate_output_filename(template, metadata, playlist_index);
         let output_path = PathBuf::from(&self.output).join(filename);
-        
+
         println!("Output file: {}", output_path.display());
-        
+
         // Initialize downloader
-        let downloader = Downloader::new(self.concurrent);
-        
+        let rate_limit = self.parse_rate_flag("--rate-limit", self.rate_limit.as_deref())?;
+        let throttled_rate = self.parse_rate_flag("--throttled-rate", self.throttled_rate.as_deref())?;
+
+        let downloader = Downloader::new(self.concurrent)
+            .with_rate_limit(rate_limit)?
+            .with_throttled_rate(throttled_rate)
+            .with_proxy(self.proxy.clone())?
+            .with_user_agent(self.user_agent.clone())?
+            .with_referer(self.referer.clone())
+            .with_headers(self.parsed_headers()?)
+            .with_timeout(std::time::Duration::from_secs(self.timeout))?
+            .with_connect_timeout(self.connect_timeout.map(std::time::Duration::from_secs))?
+            .with_tls_backend(self.tls_backend.into())?
+            .with_stream_resolver(Arc::new(self.build_youtube_extractor()?));
+
         // Download the video
         println!("Starting download...");
-        downloader.download(&metadata, output_path).await?;
-        
-        println!("Download completed!");
-        
+        downloader
+            .download_with_postprocessing(
+                metadata,
+                output_path,
+                &self.format,
+                self.ffmpeg_location.as_deref(),
+            )
+            .await?;
+
         Ok(())
     }
 }
\ No newline at end of file
@@ -8,6 +8,11 @@ pub struct Config {
     pub user_agent: String,
     pub timeout: u64,
     pub retries: usize,
+    /// Maximum total download throughput in bytes/sec, e.g. from `--rate-limit`.
+    pub rate_limit: Option<u64>,
+    /// Throughput threshold in bytes/sec below which a connection is
+    /// considered throttled and retried, e.g. from `--throttled-rate`.
+    pub throttled_rate: Option<u64>,
 }
 
 impl Default for Config {
@@ -18,6 +23,8 @@ impl Default for Config {
             user_agent: format!("yt-dlp-ng/{}", env!("CARGO_PKG_VERSION")),
             timeout: 30,
             retries: 3,
+            rate_limit: None,
+            throttled_rate: None,
         }
     }
 }
@@ -1,46 +1,531 @@
+use crate::core::format_sort;
+use crate::core::rate_limiter::RateLimiter;
 use crate::core::{VideoFormat, VideoMetadata};
+use crate::postprocessor::FfmpegPostProcessor;
 use anyhow::Result;
-use futures::StreamExt;
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tokio::fs::{File, OpenOptions};
 use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 use tracing::{info, warn};
 use std::time::Duration;
 
+/// Window over which throughput is measured to detect server-side
+/// throttling; see `Downloader::throttled_rate`. Also used by `core::hls`,
+/// which applies the same rate limiter/throttle to HLS segment fetches.
+pub(crate) const THROTTLE_WINDOW: Duration = Duration::from_secs(5);
+
+/// How often (by elapsed time) `perform_download` reports progress to the
+/// callback, so a fast connection doesn't flood it with one call per chunk.
+/// Also used by `core::hls` for the same reason.
+pub(crate) const PROGRESS_REPORT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Minimum file size before `download_format` bothers splitting the download
+/// into concurrent range requests; below this, per-segment overhead isn't
+/// worth it.
+const SEGMENTED_DOWNLOAD_THRESHOLD: u64 = 20 * 1024 * 1024;
+
+/// A point-in-time snapshot of a single file download's progress.
+#[derive(Debug, Clone, Copy)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub speed_bps: f64,
+}
+
+/// Receives progress updates during `Downloader::download_format`. Invoked
+/// from inside the download loop, so implementations should return quickly
+/// (e.g. forward to a channel) rather than doing expensive work inline.
+pub trait DownloadCallback: Send + Sync {
+    fn on_progress(&self, progress: DownloadProgress);
+}
+
+/// The default callback, matching the downloader's original hardcoded
+/// `print!`-based progress line.
+pub struct PrintlnCallback;
+
+impl DownloadCallback for PrintlnCallback {
+    fn on_progress(&self, progress: DownloadProgress) {
+        match progress.total {
+            Some(total) => {
+                let percent = (progress.downloaded as f64 / total as f64 * 100.0) as u32;
+                print!(
+                    "\rProgress: {}% ({}/{} bytes, {:.0} KB/s)",
+                    percent,
+                    progress.downloaded,
+                    total,
+                    progress.speed_bps / 1024.0
+                );
+            }
+            None => {
+                print!(
+                    "\rDownloaded: {} bytes ({:.0} KB/s)",
+                    progress.downloaded,
+                    progress.speed_bps / 1024.0
+                );
+            }
+        }
+        if progress.total.is_none_or(|total| progress.downloaded >= total) {
+            println!();
+        }
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+    }
+}
+
+/// Forwards progress updates to an mpsc channel instead of printing them,
+/// for callers (e.g. a UI) that want to observe progress from another task.
+/// Uses `try_send` rather than blocking the download loop on a full channel;
+/// a slow consumer simply misses intermediate updates.
+pub struct ChannelCallback {
+    sender: tokio::sync::mpsc::Sender<DownloadProgress>,
+}
+
+impl ChannelCallback {
+    pub fn new(sender: tokio::sync::mpsc::Sender<DownloadProgress>) -> Self {
+        Self { sender }
+    }
+}
+
+impl DownloadCallback for ChannelCallback {
+    fn on_progress(&self, progress: DownloadProgress) {
+        let _ = self.sender.try_send(progress);
+    }
+}
+
+/// A player client identity a `StreamResolver` can re-resolve formats under.
+/// YouTube mints stream URLs differently per client, so a URL 403ing for one
+/// client (e.g. the web client used during extraction) often streams fine
+/// under another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientType {
+    Web,
+    Ios,
+    Android,
+}
+
+/// Re-resolves fresh formats for a video under a specific `ClientType`, so
+/// `download_format` can recover from a persistent 403 by retrying under a
+/// different client instead of failing outright. Implemented by an extractor
+/// that knows how to talk to the source site (e.g. `YouTubeExtractor`).
+#[async_trait]
+pub trait StreamResolver: Send + Sync {
+    async fn resolve(&self, video_id: &str, client: ClientType) -> Result<Vec<VideoFormat>>;
+}
+
+fn is_throttle_error(e: &anyhow::Error) -> bool {
+    e.to_string().starts_with("throttled:")
+}
+
+/// Where a download's partial bytes live while in progress; renamed to the
+/// real output path only once the download completes successfully.
+fn part_path(output_path: &std::path::Path) -> PathBuf {
+    let mut name = output_path.as_os_str().to_owned();
+    name.push(".part");
+    PathBuf::from(name)
+}
+
+/// Sidecar recording the `ETag`/`Last-Modified` of a `.part` file's first
+/// response, so a later resume attempt can send `If-Range` and detect an
+/// upstream file that changed since.
+fn part_meta_path(output_path: &std::path::Path) -> PathBuf {
+    let mut name = output_path.as_os_str().to_owned();
+    name.push(".part.meta");
+    PathBuf::from(name)
+}
+
+async fn read_resume_validator(meta_path: &std::path::Path) -> Option<String> {
+    let validator = tokio::fs::read_to_string(meta_path).await.ok()?;
+    let validator = validator.trim();
+    (!validator.is_empty()).then(|| validator.to_string())
+}
+
+async fn write_resume_validator(meta_path: &std::path::Path, response: &reqwest::Response) {
+    let validator = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .or_else(|| response.headers().get(reqwest::header::LAST_MODIFIED))
+        .and_then(|v| v.to_str().ok());
+
+    let Some(validator) = validator else {
+        tokio::fs::remove_file(meta_path).await.ok();
+        return;
+    };
+
+    if let Err(e) = tokio::fs::write(meta_path, validator).await {
+        warn!("Failed to write resume metadata to {}: {}", meta_path.display(), e);
+    }
+}
+
+/// A resumed request only actually resumed if the server answered `206` with
+/// a `Content-Range` whose start matches what we asked for; a `200` (or a
+/// `206` at an unexpected offset) means the server ignored/invalidated the
+/// range and sent a fresh representation from byte 0.
+fn response_confirms_resume(response: &reqwest::Response, requested_start: u64) -> bool {
+    if response.status().as_u16() != 206 {
+        return false;
+    }
+
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("bytes "))
+        .and_then(|v| v.split('-').next())
+        .and_then(|start| start.parse::<u64>().ok())
+        == Some(requested_start)
+}
+
+/// The outcome of resolving a format expression like `best` or
+/// `bestvideo+bestaudio/best` against a list of available formats.
+pub enum FormatSelection<'a> {
+    Single(&'a VideoFormat),
+    Separate {
+        video: &'a VideoFormat,
+        audio: &'a VideoFormat,
+    },
+}
+
+const DEFAULT_USER_AGENT: &str =
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Which TLS implementation the underlying `reqwest::Client` is built with.
+/// Corresponds 1:1 to reqwest's `default-tls` / `native-tls` / `rustls-tls-*`
+/// Cargo features, selected here instead of at compile time so a single
+/// binary can pick a backend (e.g. rustls to avoid linking OpenSSL) via CLI
+/// flag or config rather than a feature flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TlsBackend {
+    /// reqwest's default backend for the enabled Cargo features.
+    #[default]
+    Default,
+    NativeTls,
+    RustlsWebpkiRoots,
+    RustlsNativeRoots,
+}
+
 pub struct Downloader {
     client: reqwest::Client,
     pub concurrent_limit: usize,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    throttled_rate: Option<u64>,
+    user_agent: String,
+    referer: Option<String>,
+    extra_headers: Vec<(String, String)>,
+    proxy: Option<String>,
+    callback: Arc<dyn DownloadCallback>,
+    prefer_separate_streams: bool,
+    ffmpeg_location: Option<String>,
+    timeout: Duration,
+    connect_timeout: Option<Duration>,
+    tls_backend: TlsBackend,
+    stream_resolver: Option<Arc<dyn StreamResolver>>,
+    client_order: Vec<ClientType>,
 }
 
 impl Downloader {
     pub fn new(concurrent_limit: usize) -> Self {
-        let client = reqwest::Client::builder()
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-            .timeout(std::time::Duration::from_secs(30))
-            .cookie_store(true)
-            .redirect(reqwest::redirect::Policy::limited(10))
-            .build()
-            .expect("Failed to create HTTP client");
-            
-        Self {
-            client,
+        let mut downloader = Self {
+            client: reqwest::Client::new(),
             concurrent_limit,
+            rate_limiter: None,
+            throttled_rate: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            referer: None,
+            extra_headers: Vec::new(),
+            proxy: None,
+            callback: Arc::new(PrintlnCallback),
+            prefer_separate_streams: false,
+            ffmpeg_location: None,
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: None,
+            tls_backend: TlsBackend::default(),
+            stream_resolver: None,
+            client_order: vec![ClientType::Ios, ClientType::Android, ClientType::Web],
+        };
+        downloader
+            .rebuild_client()
+            .expect("Failed to create HTTP client");
+        downloader
+    }
+
+    /// Report progress through `callback` instead of the default
+    /// `println`-based output, e.g. `ChannelCallback` to observe progress
+    /// from another task.
+    pub fn with_callback(mut self, callback: Arc<dyn DownloadCallback>) -> Self {
+        self.callback = callback;
+        self
+    }
+
+    /// Cap total download throughput to `bytes_per_sec` via a shared token
+    /// bucket, e.g. from the `--rate-limit` CLI option. Fails if
+    /// `bytes_per_sec` is `Some(0)`.
+    pub fn with_rate_limit(mut self, bytes_per_sec: Option<u64>) -> Result<Self> {
+        self.rate_limiter = match bytes_per_sec {
+            Some(rate) => Some(Arc::new(RateLimiter::new(rate)?)),
+            None => None,
+        };
+        Ok(self)
+    }
+
+    /// Abort and retry a connection whose measured throughput falls below
+    /// `bytes_per_sec` for a sustained window, to work around server-side
+    /// throttling, e.g. from the `--throttled-rate` CLI option.
+    pub fn with_throttled_rate(mut self, bytes_per_sec: Option<u64>) -> Self {
+        self.throttled_rate = bytes_per_sec;
+        self
+    }
+
+    /// Route HTTP/HTTPS/SOCKS traffic through `proxy`. An empty string forces
+    /// a direct connection, overriding any system proxy. Fails if `proxy` is
+    /// not a valid proxy URL.
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Result<Self> {
+        self.proxy = proxy;
+        self.rebuild_client()?;
+        Ok(self)
+    }
+
+    /// Override the default User-Agent sent with every request.
+    pub fn with_user_agent(mut self, user_agent: Option<String>) -> Result<Self> {
+        if let Some(user_agent) = user_agent {
+            self.user_agent = user_agent;
         }
+        self.rebuild_client()?;
+        Ok(self)
     }
-    
+
+    /// Override the default Referer header sent with download requests.
+    pub fn with_referer(mut self, referer: Option<String>) -> Self {
+        self.referer = referer;
+        self
+    }
+
+    /// Additional `KEY: VALUE` headers sent with every download request, e.g.
+    /// from repeatable `--add-header` CLI flags.
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.extra_headers = headers;
+        self
+    }
+
+    /// When set, `download()` prefers the best independent video-only +
+    /// audio-only pair (muxed with ffmpeg) over `select_best_format`'s
+    /// progressive-only pick, which otherwise caps out at whatever
+    /// resolution YouTube still serves combined.
+    pub fn with_prefer_separate_streams(mut self, prefer: bool) -> Self {
+        self.prefer_separate_streams = prefer;
+        self
+    }
+
+    /// ffmpeg binary/directory used to mux separate streams picked by
+    /// `prefer_separate_streams`. See `FfmpegPostProcessor::new`.
+    pub fn with_ffmpeg_location(mut self, ffmpeg_location: Option<String>) -> Self {
+        self.ffmpeg_location = ffmpeg_location;
+        self
+    }
+
+    /// Overall per-request timeout. Defaults to 30s.
+    pub fn with_timeout(mut self, timeout: Duration) -> Result<Self> {
+        self.timeout = timeout;
+        self.rebuild_client()?;
+        Ok(self)
+    }
+
+    /// Timeout for establishing the TCP/TLS connection, separate from the
+    /// overall request timeout. Unset by default (uses reqwest's default).
+    pub fn with_connect_timeout(mut self, connect_timeout: Option<Duration>) -> Result<Self> {
+        self.connect_timeout = connect_timeout;
+        self.rebuild_client()?;
+        Ok(self)
+    }
+
+    /// Select the TLS implementation backing the HTTP client, e.g. rustls to
+    /// avoid linking OpenSSL.
+    pub fn with_tls_backend(mut self, tls_backend: TlsBackend) -> Result<Self> {
+        self.tls_backend = tls_backend;
+        self.rebuild_client()?;
+        Ok(self)
+    }
+
+    /// Hook `download_format` uses to re-resolve a fresh format URL under a
+    /// different client (see `client_order`) once it exhausts its retries on
+    /// a persistent 403 for the current one.
+    pub fn with_stream_resolver(mut self, resolver: Arc<dyn StreamResolver>) -> Self {
+        self.stream_resolver = Some(resolver);
+        self
+    }
+
+    /// Order in which `download_format` tries alternate clients on a
+    /// persistent 403, via `stream_resolver`. Defaults to iOS, then Android,
+    /// then Web — the two mobile clients most often sidestep a 403 that hits
+    /// a web-minted URL.
+    pub fn with_client_order(mut self, client_order: Vec<ClientType>) -> Self {
+        self.client_order = client_order;
+        self
+    }
+
+    fn rebuild_client(&mut self) -> Result<()> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(self.user_agent.clone())
+            .timeout(self.timeout)
+            .cookie_store(true)
+            .redirect(reqwest::redirect::Policy::limited(10));
+
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+
+        builder = match &self.proxy {
+            Some(proxy) if proxy.is_empty() => builder.no_proxy(),
+            Some(proxy) => builder.proxy(reqwest::Proxy::all(proxy)?),
+            None => builder,
+        };
+
+        builder = match self.tls_backend {
+            TlsBackend::Default => builder,
+            TlsBackend::NativeTls => builder.use_native_tls(),
+            TlsBackend::RustlsWebpkiRoots => builder.use_rustls_tls(),
+            TlsBackend::RustlsNativeRoots => builder.use_rustls_tls().tls_built_in_root_certs(true),
+        };
+
+        self.client = builder.build()?;
+        Ok(())
+    }
+
     pub async fn download(&self, metadata: &VideoMetadata, output_path: PathBuf) -> Result<()> {
+        if self.prefer_separate_streams {
+            if let Some((video, audio)) = format_sort::best_video_audio_pair(&metadata.formats) {
+                return self
+                    .download_separate(&metadata.id, video, audio, output_path, self.ffmpeg_location.as_deref())
+                    .await;
+            }
+            warn!("prefer_separate_streams set but no separate video-only/audio-only pair found, falling back to select_best_format");
+        }
+
         // Select best format
         let format = self.select_best_format(&metadata.formats)?;
-        
+
         info!("Downloading: {} - {}", metadata.title, format.format_id);
         info!("URL: {}", format.url);
-        
+
+        if crate::core::hls::is_hls_format(format) {
+            return crate::core::hls::download_hls(
+                &self.client,
+                self.concurrent_limit,
+                &format.url,
+                &output_path,
+                &self.callback,
+                self.rate_limiter.as_ref(),
+                self.throttled_rate,
+            )
+            .await;
+        }
+
         // Download the video
-        self.download_format(format, output_path).await?;
-        
+        self.download_format(format, &metadata.id, output_path).await?;
+
         Ok(())
     }
     
+    /// Resolve a format expression against `formats`. Understands a `/`
+    /// separated fallback chain of clauses, tried left to right until one
+    /// matches: `best`, `worst`, `bestvideo+bestaudio` (separate video-only +
+    /// audio-only streams, ranked independently), `res:1080`, and codec
+    /// filters (`vcodec:vp9`, `acodec:opus`). Falls back to `select_best_format`
+    /// if no clause matches, so unrecognized expressions still degrade
+    /// gracefully instead of erroring.
+    pub fn select_format<'a>(
+        &self,
+        formats: &'a [VideoFormat],
+        expression: &str,
+    ) -> Result<FormatSelection<'a>> {
+        for clause in expression.split('/') {
+            let clause = clause.trim();
+
+            if clause == "bestvideo+bestaudio" {
+                if let Some((video, audio)) = format_sort::best_video_audio_pair(formats) {
+                    return Ok(FormatSelection::Separate { video, audio });
+                }
+                warn!("No separate video-only/audio-only pair found for '{}', trying next clause", clause);
+                continue;
+            }
+
+            if let Some(format) = format_sort::resolve_combined_clause(formats, clause) {
+                return Ok(FormatSelection::Single(format));
+            }
+        }
+
+        self.select_best_format(formats).map(FormatSelection::Single)
+    }
+
+    /// Download using a format expression, muxing separate video+audio
+    /// streams with ffmpeg when the selection calls for it.
+    pub async fn download_with_postprocessing(
+        &self,
+        metadata: &VideoMetadata,
+        output_path: PathBuf,
+        format_expression: &str,
+        ffmpeg_location: Option<&str>,
+    ) -> Result<()> {
+        match self.select_format(&metadata.formats, format_expression)? {
+            FormatSelection::Single(format) => {
+                info!("Downloading: {} - {}", metadata.title, format.format_id);
+
+                if crate::core::hls::is_hls_format(format) {
+                    return crate::core::hls::download_hls(
+                        &self.client,
+                        self.concurrent_limit,
+                        &format.url,
+                        &output_path,
+                        &self.callback,
+                        self.rate_limiter.as_ref(),
+                        self.throttled_rate,
+                    )
+                    .await;
+                }
+
+                self.download_format(format, &metadata.id, output_path).await
+            }
+            FormatSelection::Separate { video, audio } => {
+                self.download_separate(&metadata.id, video, audio, output_path, ffmpeg_location)
+                    .await
+            }
+        }
+    }
+
+    /// Download independent video-only + audio-only formats and mux them
+    /// with ffmpeg. Used both by `download_with_postprocessing`'s
+    /// `bestvideo+bestaudio`-style clauses and by `download()` when
+    /// `prefer_separate_streams` is set.
+    async fn download_separate(
+        &self,
+        video_id: &str,
+        video: &VideoFormat,
+        audio: &VideoFormat,
+        output_path: PathBuf,
+        ffmpeg_location: Option<&str>,
+    ) -> Result<()> {
+        info!(
+            "Downloading separate streams: video {} + audio {}",
+            video.format_id, audio.format_id
+        );
+
+        let tmp_dir = std::env::temp_dir();
+        let video_path = tmp_dir.join(format!("{}.video.{}", video_id, video.ext));
+        let audio_path = tmp_dir.join(format!("{}.audio.{}", video_id, audio.ext));
+
+        self.download_format(video, video_id, video_path.clone()).await?;
+        self.download_format(audio, video_id, audio_path.clone()).await?;
+
+        let postprocessor = FfmpegPostProcessor::new(ffmpeg_location)?;
+        postprocessor
+            .mux(&video_path, &audio_path, &output_path)
+            .await
+    }
+
     pub fn select_best_format<'a>(&self, formats: &'a [VideoFormat]) -> Result<&'a VideoFormat> {
         // Simple selection: prefer mp4, then highest resolution
         let best = formats
@@ -58,54 +543,121 @@ impl Downloader {
         best.ok_or_else(|| anyhow::anyhow!("No suitable format found"))
     }
     
-    async fn download_format(&self, format: &VideoFormat, output_path: PathBuf) -> Result<()> {
-        // Check if partial file exists for resume capability
-        let resume_from = if output_path.exists() {
-            match tokio::fs::metadata(&output_path).await {
-                Ok(metadata) => {
-                    let size = metadata.len();
-                    info!("Found partial file, resuming from {} bytes", size);
-                    Some(size)
+    /// Build a request carrying the same anti-detection headers used for
+    /// every download/probe request, plus any `--add-header` overrides.
+    fn build_request(&self, url: &str) -> reqwest::RequestBuilder {
+        let mut request = self.client
+            .get(url)
+            .header("Accept", "*/*")
+            .header("Accept-Language", "en-US,en;q=0.9")
+            .header("Accept-Encoding", "gzip, deflate, br")
+            .header("Cache-Control", "no-cache")
+            .header("Connection", "keep-alive")
+            .header("Pragma", "no-cache")
+            .header(
+                "Referer",
+                self.referer.clone().unwrap_or_else(|| "https://www.youtube.com/".to_string()),
+            )
+            .header("Origin", "https://www.youtube.com")
+            .header("Sec-Fetch-Dest", "video")
+            .header("Sec-Fetch-Mode", "no-cors")
+            .header("Sec-Fetch-Site", "cross-site")
+            .header("Sec-Ch-Ua", "\"Not_A Brand\";v=\"8\", \"Chromium\";v=\"120\", \"Google Chrome\";v=\"120\"")
+            .header("Sec-Ch-Ua-Mobile", "?0")
+            .header("Sec-Ch-Ua-Platform", "\"Windows\"")
+            .header("Upgrade-Insecure-Requests", "1")
+            .header("X-Client-Data", "CgSLywE=")
+            .header("X-Youtube-Client-Name", "1")
+            .header("X-Youtube-Client-Version", "2.20231201.00.00");
+
+        for (key, value) in &self.extra_headers {
+            request = request.header(key, value);
+        }
+
+        request
+    }
+
+    /// Probe whether `url` serves byte-range requests by issuing a
+    /// `Range: bytes=0-0` request. Returns the full content length if the
+    /// server answers `206` (or advertises `Accept-Ranges: bytes` on a `200`),
+    /// `None` otherwise so the caller falls back to a single-stream download.
+    async fn probe_range_support(&self, url: &str) -> Option<u64> {
+        let response = self
+            .build_request(url)
+            .header("Range", "bytes=0-0")
+            .send()
+            .await
+            .ok()?;
+
+        if response.status().as_u16() == 206 {
+            let content_range = response.headers().get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+            return content_range.rsplit('/').next()?.parse().ok();
+        }
+
+        if response.status().is_success() {
+            let accepts_ranges = response
+                .headers()
+                .get(reqwest::header::ACCEPT_RANGES)
+                .is_some_and(|v| v == "bytes");
+            if accepts_ranges {
+                return response.content_length();
+            }
+        }
+
+        None
+    }
+
+    async fn download_format(&self, format: &VideoFormat, video_id: &str, output_path: PathBuf) -> Result<()> {
+        if self.concurrent_limit > 1 {
+            if let Some(total) = self.probe_range_support(&format.url).await {
+                if total >= SEGMENTED_DOWNLOAD_THRESHOLD {
+                    match self.download_segmented(&format.url, &output_path, total).await {
+                        Ok(()) => return Ok(()),
+                        Err(e) => warn!(
+                            "Segmented download failed, falling back to single-stream: {}",
+                            e
+                        ),
+                    }
                 }
-                Err(_) => None
             }
-        } else {
-            None
-        };
-        
-        // Retry logic with exponential backoff for 403 errors
+        }
+
+        // Retry logic with exponential backoff for 403 errors and throttling
         const MAX_RETRIES: u32 = 3;
         let mut attempt = 0;
-        
+        let part_path = part_path(&output_path);
+        let meta_path = part_meta_path(&output_path);
+        let mut url = format.url.clone();
+        let mut next_client = 0usize;
+
         loop {
             attempt += 1;
-            
-            // Build request with enhanced anti-detection headers
-            let mut request = self.client
-                .get(&format.url)
-                .header("Accept", "*/*")
-                .header("Accept-Language", "en-US,en;q=0.9")
-                .header("Accept-Encoding", "gzip, deflate, br")
-                .header("Cache-Control", "no-cache")
-                .header("Connection", "keep-alive")
-                .header("Pragma", "no-cache")
-                .header("Referer", "https://www.youtube.com/")
-                .header("Origin", "https://www.youtube.com")
-                .header("Sec-Fetch-Dest", "video")
-                .header("Sec-Fetch-Mode", "no-cors")
-                .header("Sec-Fetch-Site", "cross-site")
-                .header("Sec-Ch-Ua", "\"Not_A Brand\";v=\"8\", \"Chromium\";v=\"120\", \"Google Chrome\";v=\"120\"")
-                .header("Sec-Ch-Ua-Mobile", "?0")
-                .header("Sec-Ch-Ua-Platform", "\"Windows\"")
-                .header("Upgrade-Insecure-Requests", "1")
-                .header("X-Client-Data", "CgSLywE=")
-                .header("X-Youtube-Client-Name", "1")
-                .header("X-Youtube-Client-Version", "2.20231201.00.00");
-            
+
+            // Re-check the partial file on every attempt: a throttled
+            // download may have already appended bytes to it before aborting.
+            let mut resume_from = match tokio::fs::metadata(&part_path).await {
+                Ok(metadata) if metadata.len() > 0 => {
+                    let size = metadata.len();
+                    info!("Found partial file, resuming from {} bytes", size);
+                    Some(size)
+                }
+                _ => None,
+            };
+
+            let validator = read_resume_validator(&meta_path).await;
+
+            let mut request = self.build_request(&url);
+
             if let Some(resume_pos) = resume_from {
                 request = request.header("Range", format!("bytes={}-", resume_pos));
+                if let Some(validator) = &validator {
+                    // Forces the server to either honor the range against the
+                    // same representation or send a fresh 200, instead of
+                    // silently resuming against a file that changed upstream.
+                    request = request.header("If-Range", validator.clone());
+                }
             }
-            
+
             let response = match request.send().await {
                 Ok(response) => response,
                 Err(e) => {
@@ -117,28 +669,104 @@ impl Downloader {
                     continue;
                 }
             };
-            
+
             let status = response.status();
-            
+
             if status.is_success() || status.as_u16() == 206 {
-                // Success - proceed with download
-                return self.perform_download(response, output_path, resume_from).await;
+                if let Some(requested) = resume_from {
+                    if !response_confirms_resume(&response, requested) {
+                        warn!(
+                            "Server did not honor resume at byte {} (status {}); restarting from scratch",
+                            requested, status
+                        );
+                        tokio::fs::remove_file(&part_path).await.ok();
+                        resume_from = None;
+                    }
+                }
+
+                if resume_from.is_none() {
+                    write_resume_validator(&meta_path, &response).await;
+                }
+
+                match self
+                    .perform_download(response, part_path.clone(), resume_from)
+                    .await
+                {
+                    Ok(()) => {
+                        tokio::fs::rename(&part_path, &output_path).await?;
+                        tokio::fs::remove_file(&meta_path).await.ok();
+                        return Ok(());
+                    }
+                    Err(e) if is_throttle_error(&e) && attempt < MAX_RETRIES => {
+                        warn!("Throttling detected (attempt {}), retrying: {}", attempt, e);
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                }
             } else if status.as_u16() == 403 && attempt < MAX_RETRIES {
                 // 403 Forbidden - retry with backoff
                 warn!("HTTP 403 error (attempt {}), retrying in {} seconds...", attempt, 2_u64.pow(attempt));
                 tokio::time::sleep(Duration::from_secs(2_u64.pow(attempt))).await;
                 continue;
+            } else if status.as_u16() == 403 {
+                // Retries on the current client are exhausted; re-resolve the
+                // format under the next client instead of failing outright.
+                match self
+                    .resolve_via_next_client(video_id, &format.format_id, &mut next_client)
+                    .await
+                {
+                    Some(new_url) => {
+                        url = new_url;
+                        attempt = 0;
+                        continue;
+                    }
+                    None => anyhow::bail!(
+                        "Failed to download after {} attempts: HTTP 403 (no alternate client resolved a working URL)",
+                        attempt
+                    ),
+                }
             } else {
                 // Other errors or max retries exceeded
                 anyhow::bail!("Failed to download after {} attempts: HTTP {}", attempt, status);
             }
         }
     }
-    
+
+    /// Walks `self.client_order` starting at `*next_client`, asking
+    /// `self.stream_resolver` to re-resolve `video_id`'s formats under each
+    /// client in turn until one yields a format matching `format_id`.
+    /// Advances `*next_client` past whichever client it used (success or
+    /// not), so a later call resumes from the next untried client.
+    async fn resolve_via_next_client(
+        &self,
+        video_id: &str,
+        format_id: &str,
+        next_client: &mut usize,
+    ) -> Option<String> {
+        let resolver = self.stream_resolver.as_ref()?;
+
+        while let Some(&client) = self.client_order.get(*next_client) {
+            *next_client += 1;
+
+            match resolver.resolve(video_id, client).await {
+                Ok(formats) => {
+                    if let Some(format) = formats.iter().find(|f| f.format_id == format_id) {
+                        info!("Re-resolved format {} under {:?} client", format_id, client);
+                        return Some(format.url.clone());
+                    }
+                    warn!("{:?} client did not offer a matching format {}", client, format_id);
+                }
+                Err(e) => warn!("Failed to re-resolve via {:?} client: {}", client, e),
+            }
+        }
+
+        None
+    }
+
     async fn perform_download(&self, response: reqwest::Response, output_path: PathBuf, resume_from: Option<u64>) -> Result<()> {
         let total_size = response.content_length();
         let mut downloaded = resume_from.unwrap_or(0);
-        
+
         // Open file in append mode if resuming, create new otherwise
         let mut file = if resume_from.is_some() {
             let mut file = OpenOptions::new()
@@ -161,37 +789,253 @@ impl Downloader {
             total_size
         };
         
-        println!(
-            "Downloading {} bytes...", 
+        info!(
+            "Downloading {} bytes...",
             expected_total.map_or("unknown".to_string(), |s| s.to_string())
         );
-        
-        if resume_from.is_some() {
-            println!("Resuming from {} bytes", resume_from.unwrap());
-        }
-        
+
+        let mut window_start = std::time::Instant::now();
+        let mut window_bytes: u64 = 0;
+
+        let mut report_start = std::time::Instant::now();
+        let mut report_bytes: u64 = 0;
+
         while let Some(chunk) = stream.next().await {
             let chunk = chunk?;
-            downloaded += chunk.len() as u64;
+            let chunk_len = chunk.len() as u64;
+
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire(chunk_len).await;
+            }
+
+            downloaded += chunk_len;
+            window_bytes += chunk_len;
+            report_bytes += chunk_len;
             file.write_all(&chunk).await?;
-            
-            // Progress reporting
-            if let Some(total) = expected_total {
-                let progress = (downloaded as f64 / total as f64 * 100.0) as u32;
-                print!("\rProgress: {}% ({}/{} bytes)", progress, downloaded, total);
-                std::io::Write::flush(&mut std::io::stdout()).unwrap();
-            } else {
-                if downloaded % (1024 * 1024) == 0 { // Report every MB
-                    print!("\rDownloaded: {} bytes", downloaded);
-                    std::io::Write::flush(&mut std::io::stdout()).unwrap();
+
+            if let Some(threshold) = self.throttled_rate {
+                let elapsed = window_start.elapsed();
+                if elapsed >= THROTTLE_WINDOW {
+                    let measured_rate = window_bytes as f64 / elapsed.as_secs_f64();
+                    if measured_rate < threshold as f64 {
+                        file.flush().await?;
+                        anyhow::bail!(
+                            "throttled: measured {:.0} B/s below threshold {} B/s",
+                            measured_rate,
+                            threshold
+                        );
+                    }
+                    window_start = std::time::Instant::now();
+                    window_bytes = 0;
                 }
             }
+
+            let report_elapsed = report_start.elapsed();
+            if report_elapsed >= PROGRESS_REPORT_INTERVAL {
+                let speed_bps = report_bytes as f64 / report_elapsed.as_secs_f64();
+                self.callback.on_progress(DownloadProgress {
+                    downloaded,
+                    total: expected_total,
+                    speed_bps,
+                });
+                report_start = std::time::Instant::now();
+                report_bytes = 0;
+            }
         }
-        
-        println!(); // New line after progress
+
+        self.callback.on_progress(DownloadProgress {
+            downloaded,
+            total: expected_total,
+            speed_bps: 0.0,
+        });
+
         file.flush().await?;
         info!("Downloaded to: {}", output_path.display());
-        
+
+        Ok(())
+    }
+
+    /// Download `url` (known to be `total` bytes and to support byte ranges)
+    /// as `self.concurrent_limit` concurrent `Range` requests, each writing
+    /// directly into its slice of a pre-allocated `.part` file. Only renamed
+    /// to `output_path` once every segment is verified complete and the file
+    /// is `sync_all`'d, so a dropped connection mid-segment never leaves a
+    /// silently-truncated file at the user-visible path.
+    async fn download_segmented(&self, url: &str, output_path: &std::path::Path, total: u64) -> Result<()> {
+        let segment_count = self.concurrent_limit.max(1);
+        let chunk_size = total.div_ceil(segment_count as u64);
+
+        let part_path = part_path(output_path);
+        let file = File::create(&part_path).await?;
+        file.set_len(total).await?;
+
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        while start < total {
+            let end = (start + chunk_size - 1).min(total - 1);
+            ranges.push((start, end));
+            start = end + 1;
+        }
+
+        info!("Downloading {} bytes across {} segments", total, ranges.len());
+
+        let downloaded = Arc::new(AtomicU64::new(0));
+
+        let results: Vec<Result<()>> = stream::iter(ranges)
+            .map(|(start, end)| {
+                let file = &file;
+                let downloaded = downloaded.clone();
+                async move {
+                    self.download_segment(url, start, end, file, total, &downloaded)
+                        .await
+                }
+            })
+            .buffer_unordered(segment_count)
+            .collect()
+            .await;
+
+        if let Some(e) = results.into_iter().find_map(|result| result.err()) {
+            // The `.part` file is pre-sized to `total` via `set_len`, so a
+            // later single-stream retry must not find it and mistake it for
+            // a fully-downloaded file to resume from.
+            drop(file);
+            tokio::fs::remove_file(&part_path).await.ok();
+            return Err(e);
+        }
+
+        self.callback.on_progress(DownloadProgress {
+            downloaded: downloaded.load(Ordering::Relaxed),
+            total: Some(total),
+            speed_bps: 0.0,
+        });
+
+        file.sync_all().await?;
+        drop(file);
+        tokio::fs::rename(&part_path, output_path).await?;
+        info!("Downloaded to: {}", output_path.display());
+
+        Ok(())
+    }
+
+    /// Fetch `[start, end]` of `url` and write it at that absolute offset in
+    /// `file`, retrying just this range (with the same exponential backoff as
+    /// `download_format`) if the request or write fails.
+    async fn download_segment(
+        &self,
+        url: &str,
+        start: u64,
+        end: u64,
+        file: &File,
+        total: u64,
+        downloaded: &Arc<AtomicU64>,
+    ) -> Result<()> {
+        const MAX_RETRIES: u32 = 3;
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            match self
+                .fetch_segment_range(url, start, end, file, total, downloaded)
+                .await
+            {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < MAX_RETRIES => {
+                    warn!(
+                        "Segment bytes={}-{} failed (attempt {}), retrying: {}",
+                        start, end, attempt, e
+                    );
+                    tokio::time::sleep(Duration::from_secs(2_u64.pow(attempt))).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn fetch_segment_range(
+        &self,
+        url: &str,
+        start: u64,
+        end: u64,
+        file: &File,
+        total: u64,
+        downloaded: &Arc<AtomicU64>,
+    ) -> Result<()> {
+        let response = self
+            .build_request(url)
+            .header("Range", format!("bytes={}-{}", start, end))
+            .send()
+            .await?;
+
+        if !response.status().is_success() && response.status().as_u16() != 206 {
+            anyhow::bail!("Segment request failed: HTTP {}", response.status());
+        }
+
+        let mut file = file.try_clone().await?;
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+
+        let expected_bytes = end - start + 1;
+        let mut segment_bytes: u64 = 0;
+
+        let mut stream = response.bytes_stream();
+        let mut report_start = std::time::Instant::now();
+        let mut report_bytes: u64 = 0;
+
+        let mut window_start = std::time::Instant::now();
+        let mut window_bytes: u64 = 0;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            let chunk_len = chunk.len() as u64;
+
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire(chunk_len).await;
+            }
+
+            file.write_all(&chunk).await?;
+            segment_bytes += chunk_len;
+            window_bytes += chunk_len;
+
+            let total_downloaded = downloaded.fetch_add(chunk_len, Ordering::Relaxed) + chunk_len;
+            report_bytes += chunk_len;
+
+            if let Some(threshold) = self.throttled_rate {
+                let elapsed = window_start.elapsed();
+                if elapsed >= THROTTLE_WINDOW {
+                    let measured_rate = window_bytes as f64 / elapsed.as_secs_f64();
+                    if measured_rate < threshold as f64 {
+                        anyhow::bail!(
+                            "throttled: measured {:.0} B/s below threshold {} B/s",
+                            measured_rate,
+                            threshold
+                        );
+                    }
+                    window_start = std::time::Instant::now();
+                    window_bytes = 0;
+                }
+            }
+
+            let report_elapsed = report_start.elapsed();
+            if report_elapsed >= PROGRESS_REPORT_INTERVAL {
+                self.callback.on_progress(DownloadProgress {
+                    downloaded: total_downloaded,
+                    total: Some(total),
+                    speed_bps: report_bytes as f64 / report_elapsed.as_secs_f64(),
+                });
+                report_start = std::time::Instant::now();
+                report_bytes = 0;
+            }
+        }
+
+        if segment_bytes != expected_bytes {
+            anyhow::bail!(
+                "Segment bytes={}-{} truncated: expected {} bytes, got {}",
+                start,
+                end,
+                expected_bytes,
+                segment_bytes
+            );
+        }
+
         Ok(())
     }
 }
\ No newline at end of file
@@ -1,13 +1,23 @@
-use crate::core::VideoMetadata;
+use crate::core::{Playlist, VideoMetadata};
+use crate::extractors::YouTubeExtractor;
 use anyhow::Result;
 use async_trait::async_trait;
 use url::Url;
 
+/// The result of extracting a URL: either a single video, or a playlist/channel
+/// listing of multiple videos. Mirrors how the `youtube_dl` crate models output
+/// as either one video or a playlist of videos.
+#[derive(Debug, Clone)]
+pub enum ExtractionResult {
+    SingleVideo(VideoMetadata),
+    Playlist(Playlist),
+}
+
 #[async_trait]
 pub trait Extractor: Send + Sync {
     fn name(&self) -> &'static str;
     fn suitable(&self, url: &Url) -> bool;
-    async fn extract(&mut self, url: &Url) -> Result<VideoMetadata>;
+    async fn extract(&mut self, url: &Url) -> Result<ExtractionResult>;
 }
 
 pub struct ExtractorEngine {
@@ -17,18 +27,30 @@ pub struct ExtractorEngine {
 impl ExtractorEngine {
     pub fn new() -> Self {
         Self {
-            extractors: vec![
-                // TODO: Add built-in extractors
-            ],
+            extractors: vec![Box::new(YouTubeExtractor::new())],
         }
     }
 
+    /// Register `extractor`, replacing any existing extractor with the same
+    /// `name()` rather than appending a duplicate — e.g. a caller swapping in
+    /// a `YouTubeExtractor` configured with proxy/header flags in place of
+    /// the unconfigured one `new()` registers by default.
     pub fn register_extractor(&mut self, extractor: Box<dyn Extractor>) {
-        self.extractors.push(extractor);
+        if let Some(existing) = self.extractors.iter_mut().find(|e| e.name() == extractor.name()) {
+            *existing = extractor;
+        } else {
+            self.extractors.push(extractor);
+        }
     }
 
-    pub async fn extract(&mut self, url: &str) -> Result<VideoMetadata> {
-        let parsed_url = Url::parse(url)?;
+    pub async fn extract(&mut self, url: &str) -> Result<ExtractionResult> {
+        // Protocol-relative links (`//youtube.com/watch?v=...`) aren't valid
+        // absolute URLs on their own; assume https like a browser would.
+        let url = match url.strip_prefix("//") {
+            Some(rest) => std::borrow::Cow::Owned(format!("https://{}", rest)),
+            None => std::borrow::Cow::Borrowed(url),
+        };
+        let parsed_url = Url::parse(&url)?;
 
         for extractor in &mut self.extractors {
             if extractor.suitable(&parsed_url) {
@@ -0,0 +1,184 @@
+//! Ranks and filters `VideoFormat`s so callers can pick by expression
+//! (`best`, `worst`, `res:1080`, `vcodec:vp9`, ...) instead of hand-filtering
+//! the raw `Vec<VideoFormat>` a `VideoMetadata` carries.
+
+use crate::core::VideoFormat;
+
+/// A tunable preference vector: resolution, then fps, then bitrate, then
+/// codec preference, then container. Larger is better; compare tuples
+/// directly to rank two formats.
+fn format_rank(format: &VideoFormat) -> (u32, i64, i64, i32, i32) {
+    let height = format
+        .resolution
+        .as_deref()
+        .and_then(|r| r.split_once('x'))
+        .and_then(|(_, h)| h.parse::<u32>().ok())
+        .unwrap_or(0);
+
+    let fps = format.fps.unwrap_or(0.0) as i64;
+    let tbr = format.tbr.unwrap_or(0.0) as i64;
+    let codec_rank = codec_preference(format.vcodec.as_deref(), &["av01", "vp9", "avc1", "h264"])
+        + codec_preference(format.acodec.as_deref(), &["opus", "aac", "mp4a"]);
+    let container_rank = match format.ext.as_str() {
+        "mp4" => 2,
+        "webm" => 1,
+        _ => 0,
+    };
+
+    (height, fps, tbr, codec_rank, container_rank)
+}
+
+/// Higher is more preferred; codecs not in `preference` rank lowest (but
+/// still above no codec at all, since the caller already filtered on
+/// `is_some()` before ranking).
+fn codec_preference(codec: Option<&str>, preference: &[&str]) -> i32 {
+    let Some(codec) = codec else { return 0 };
+    preference
+        .iter()
+        .position(|prefix| codec.starts_with(prefix))
+        .map(|i| (preference.len() - i) as i32)
+        .unwrap_or(0)
+}
+
+/// The requested height from a `res:1080`-style clause, or `None` if the
+/// field isn't a `res:` clause at all.
+fn parse_res_clause(clause: &str) -> Option<u32> {
+    clause.strip_prefix("res:").and_then(|v| v.parse().ok())
+}
+
+/// Highest-ranked format among `formats` matching `predicate`.
+pub fn best_matching<'a>(
+    formats: &'a [VideoFormat],
+    predicate: impl Fn(&VideoFormat) -> bool,
+) -> Option<&'a VideoFormat> {
+    formats
+        .iter()
+        .filter(|f| predicate(f))
+        .max_by(|a, b| format_rank(a).cmp(&format_rank(b)))
+}
+
+/// Lowest-ranked format among `formats` matching `predicate`.
+pub fn worst_matching<'a>(
+    formats: &'a [VideoFormat],
+    predicate: impl Fn(&VideoFormat) -> bool,
+) -> Option<&'a VideoFormat> {
+    formats
+        .iter()
+        .filter(|f| predicate(f))
+        .min_by(|a, b| format_rank(a).cmp(&format_rank(b)))
+}
+
+/// Resolve one selection clause (no `/` fallback chain) against a combined
+/// (video+audio) format list. Returns `None` for a clause this module
+/// doesn't understand or that matched nothing, letting the caller try the
+/// next fallback clause.
+pub fn resolve_combined_clause<'a>(formats: &'a [VideoFormat], clause: &str) -> Option<&'a VideoFormat> {
+    let has_both = |f: &VideoFormat| f.vcodec.is_some() && f.acodec.is_some();
+
+    match clause {
+        "best" => best_matching(formats, has_both),
+        "worst" => worst_matching(formats, has_both),
+        clause if clause.starts_with("res:") => {
+            let target = parse_res_clause(clause)?;
+            best_matching(formats, |f| {
+                has_both(f)
+                    && f.resolution
+                        .as_deref()
+                        .and_then(|r| r.split_once('x'))
+                        .and_then(|(_, h)| h.parse::<u32>().ok())
+                        == Some(target)
+            })
+        }
+        clause if clause.starts_with("vcodec:") => {
+            let codec = &clause["vcodec:".len()..];
+            best_matching(formats, |f| {
+                has_both(f) && f.vcodec.as_deref().is_some_and(|v| v.starts_with(codec))
+            })
+        }
+        clause if clause.starts_with("acodec:") => {
+            let codec = &clause["acodec:".len()..];
+            best_matching(formats, |f| {
+                has_both(f) && f.acodec.as_deref().is_some_and(|a| a.starts_with(codec))
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Best video-only and audio-only formats, for `bestvideo+bestaudio`-style
+/// expressions that mux two separate streams.
+pub fn best_video_audio_pair(formats: &[VideoFormat]) -> Option<(&VideoFormat, &VideoFormat)> {
+    let video = best_matching(formats, |f| f.vcodec.is_some() && f.acodec.is_none());
+    let audio = best_matching(formats, |f| f.acodec.is_some() && f.vcodec.is_none());
+    match (video, audio) {
+        (Some(video), Some(audio)) => Some((video, audio)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(id: &str, ext: &str, height: Option<u32>, vcodec: Option<&str>, acodec: Option<&str>, tbr: f64) -> VideoFormat {
+        VideoFormat {
+            format_id: id.to_string(),
+            url: format!("https://example.com/{}", id),
+            quality: None,
+            resolution: height.map(|h| format!("{}x{}", h * 16 / 9, h)),
+            fps: Some(30.0),
+            vcodec: vcodec.map(|s| s.to_string()),
+            acodec: acodec.map(|s| s.to_string()),
+            ext: ext.to_string(),
+            filesize: None,
+            tbr: Some(tbr),
+            vbr: None,
+            abr: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_combined_clause_res() {
+        let formats = vec![
+            format("a", "mp4", Some(480), Some("h264"), Some("aac"), 500.0),
+            format("b", "mp4", Some(1080), Some("h264"), Some("aac"), 2000.0),
+        ];
+
+        let selected = resolve_combined_clause(&formats, "res:1080").unwrap();
+        assert_eq!(selected.format_id, "b");
+    }
+
+    #[test]
+    fn test_resolve_combined_clause_worst() {
+        let formats = vec![
+            format("a", "mp4", Some(480), Some("h264"), Some("aac"), 500.0),
+            format("b", "mp4", Some(1080), Some("h264"), Some("aac"), 2000.0),
+        ];
+
+        let selected = resolve_combined_clause(&formats, "worst").unwrap();
+        assert_eq!(selected.format_id, "a");
+    }
+
+    #[test]
+    fn test_resolve_combined_clause_vcodec_filter() {
+        let formats = vec![
+            format("a", "mp4", Some(1080), Some("avc1.640028"), Some("aac"), 2000.0),
+            format("b", "webm", Some(1080), Some("vp9"), Some("opus"), 1800.0),
+        ];
+
+        let selected = resolve_combined_clause(&formats, "vcodec:vp9").unwrap();
+        assert_eq!(selected.format_id, "b");
+    }
+
+    #[test]
+    fn test_best_video_audio_pair() {
+        let formats = vec![
+            format("video", "mp4", Some(1080), Some("avc1"), None, 2000.0),
+            format("audio", "m4a", None, None, Some("aac"), 128.0),
+        ];
+
+        let (video, audio) = best_video_audio_pair(&formats).unwrap();
+        assert_eq!(video.format_id, "video");
+        assert_eq!(audio.format_id, "audio");
+    }
+}
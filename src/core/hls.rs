@@ -0,0 +1,351 @@
+use crate::core::downloader::{DownloadCallback, DownloadProgress, PROGRESS_REPORT_INTERVAL, THROTTLE_WINDOW};
+use crate::core::rate_limiter::RateLimiter;
+use crate::core::VideoFormat;
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::{BlockDecryptMut, KeyIvInit};
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, warn};
+use url::Url;
+
+type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+
+/// A single media-playlist segment with its decryption key (if any) and byte
+/// range (if the playlist uses `#EXT-X-BYTERANGE`).
+#[derive(Debug, Clone)]
+struct Segment {
+    url: String,
+    byte_range: Option<(u64, u64)>,
+    key: Option<SegmentKey>,
+}
+
+#[derive(Debug, Clone)]
+struct SegmentKey {
+    key_url: String,
+    iv: [u8; 16],
+}
+
+/// Returns true when a format's URL/extension indicates an HLS playlist
+/// (`application/x-mpegURL` / `.m3u8`) rather than a single progressive file.
+pub fn is_hls_format(format: &VideoFormat) -> bool {
+    format.ext == "m3u8" || format.url.contains(".m3u8")
+}
+
+/// Download and concatenate an HLS stream (master or media playlist) into a
+/// single output file, decrypting AES-128-CBC segments when a key is present.
+/// Progress is reported through `callback` the same way `Downloader`'s
+/// progressive/segmented download paths do, rather than printing directly.
+/// `rate_limiter`/`throttled_rate` apply the same `--rate-limit`/
+/// `--throttled-rate` controls those paths honor, so an HLS-served format
+/// doesn't silently bypass them.
+pub async fn download_hls(
+    client: &reqwest::Client,
+    client_concurrency: usize,
+    playlist_url: &str,
+    output_path: &Path,
+    callback: &Arc<dyn DownloadCallback>,
+    rate_limiter: Option<&Arc<RateLimiter>>,
+    throttled_rate: Option<u64>,
+) -> Result<()> {
+    let media_playlist_url = resolve_media_playlist(client, playlist_url).await?;
+    let playlist_text = client.get(&media_playlist_url).send().await?.text().await?;
+    let segments = parse_media_playlist(&media_playlist_url, &playlist_text)?;
+
+    if segments.is_empty() {
+        anyhow::bail!("HLS media playlist contained no segments: {}", media_playlist_url);
+    }
+
+    info!("Downloading {} HLS segments -> {}", segments.len(), output_path.display());
+
+    let mut file = tokio::fs::File::create(output_path).await?;
+    let downloaded = Arc::new(AtomicU64::new(0));
+    let key_cache: Arc<tokio::sync::Mutex<std::collections::HashMap<String, [u8; 16]>>> =
+        Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new()));
+
+    let concurrency = client_concurrency.max(1);
+
+    // `buffered` preserves input order while still fetching up to
+    // `concurrency` segments in parallel, so writes stay sequential.
+    let mut results = stream::iter(segments.into_iter().enumerate())
+        .map(|(index, segment)| {
+            let client = client.clone();
+            let key_cache = key_cache.clone();
+            let rate_limiter = rate_limiter.cloned();
+            async move {
+                let bytes = fetch_segment(&client, &segment, key_cache, rate_limiter).await;
+                (index, bytes)
+            }
+        })
+        .buffered(concurrency);
+
+    let mut next_expected = 0usize;
+    let mut report_start = std::time::Instant::now();
+    let mut report_bytes: u64 = 0;
+    let mut window_start = std::time::Instant::now();
+    let mut window_bytes: u64 = 0;
+
+    while let Some((index, bytes)) = results.next().await {
+        let bytes = bytes.with_context(|| format!("Failed to download HLS segment {}", index))?;
+        debug_assert_eq!(index, next_expected, "HLS segments must be written in order");
+        next_expected += 1;
+
+        file.write_all(&bytes).await?;
+        let chunk_len = bytes.len() as u64;
+        let total = downloaded.fetch_add(chunk_len, Ordering::Relaxed) + chunk_len;
+        report_bytes += chunk_len;
+        window_bytes += chunk_len;
+
+        if let Some(threshold) = throttled_rate {
+            let elapsed = window_start.elapsed();
+            if elapsed >= THROTTLE_WINDOW {
+                let measured_rate = window_bytes as f64 / elapsed.as_secs_f64();
+                if measured_rate < threshold as f64 {
+                    file.flush().await?;
+                    anyhow::bail!(
+                        "throttled: measured {:.0} B/s below threshold {} B/s",
+                        measured_rate,
+                        threshold
+                    );
+                }
+                window_start = std::time::Instant::now();
+                window_bytes = 0;
+            }
+        }
+
+        let report_elapsed = report_start.elapsed();
+        if report_elapsed >= PROGRESS_REPORT_INTERVAL {
+            callback.on_progress(DownloadProgress {
+                downloaded: total,
+                total: None,
+                speed_bps: report_bytes as f64 / report_elapsed.as_secs_f64(),
+            });
+            report_start = std::time::Instant::now();
+            report_bytes = 0;
+        }
+    }
+
+    callback.on_progress(DownloadProgress {
+        downloaded: downloaded.load(Ordering::Relaxed),
+        total: None,
+        speed_bps: 0.0,
+    });
+
+    file.flush().await?;
+    info!("HLS download complete: {}", output_path.display());
+
+    Ok(())
+}
+
+async fn fetch_segment(
+    client: &reqwest::Client,
+    segment: &Segment,
+    key_cache: Arc<tokio::sync::Mutex<std::collections::HashMap<String, [u8; 16]>>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+) -> Result<Vec<u8>> {
+    let mut request = client.get(&segment.url);
+    if let Some((start, end)) = segment.byte_range {
+        request = request.header("Range", format!("bytes={}-{}", start, end));
+    }
+
+    let bytes = request.send().await?.bytes().await?.to_vec();
+
+    if let Some(limiter) = &rate_limiter {
+        limiter.acquire(bytes.len() as u64).await;
+    }
+
+    if let Some(key) = &segment.key {
+        let key_bytes = {
+            let mut cache = key_cache.lock().await;
+            if let Some(cached) = cache.get(&key.key_url) {
+                *cached
+            } else {
+                let fetched = client.get(&key.key_url).send().await?.bytes().await?;
+                if fetched.len() != 16 {
+                    anyhow::bail!("Unexpected AES-128 key length: {}", fetched.len());
+                }
+                let mut buf = [0u8; 16];
+                buf.copy_from_slice(&fetched);
+                cache.insert(key.key_url.clone(), buf);
+                buf
+            }
+        };
+
+        return decrypt_segment(&bytes, &key_bytes, &key.iv);
+    }
+
+    Ok(bytes)
+}
+
+fn decrypt_segment(data: &[u8], key: &[u8; 16], iv: &[u8; 16]) -> Result<Vec<u8>> {
+    let mut buf = data.to_vec();
+    let decryptor = Aes128CbcDec::new(key.into(), iv.into());
+    let plaintext = decryptor
+        .decrypt_padded_mut::<Pkcs7>(&mut buf)
+        .map_err(|e| anyhow::anyhow!("AES-128-CBC decryption failed: {}", e))?;
+    Ok(plaintext.to_vec())
+}
+
+/// If `playlist_url` is a master playlist, pick the highest-bandwidth
+/// variant and return its media playlist URL; otherwise return it unchanged.
+async fn resolve_media_playlist(client: &reqwest::Client, playlist_url: &str) -> Result<String> {
+    let text = client.get(playlist_url).send().await?.text().await?;
+
+    if !text.contains("#EXT-X-STREAM-INF") {
+        return Ok(playlist_url.to_string());
+    }
+
+    let mut best_bandwidth = 0u64;
+    let mut best_uri: Option<String> = None;
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            let bandwidth = attrs
+                .split(',')
+                .find_map(|kv| kv.trim().strip_prefix("BANDWIDTH="))
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(0);
+
+            if let Some(uri_line) = lines.peek() {
+                if !uri_line.starts_with('#') && bandwidth >= best_bandwidth {
+                    best_bandwidth = bandwidth;
+                    best_uri = Some(uri_line.to_string());
+                }
+            }
+        }
+    }
+
+    let variant_uri = best_uri.ok_or_else(|| anyhow::anyhow!("No variant found in master playlist"))?;
+    Ok(resolve_url(playlist_url, &variant_uri))
+}
+
+fn parse_media_playlist(base_url: &str, text: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut current_key: Option<SegmentKey> = None;
+    let mut current_byte_range: Option<(u64, u64)> = None;
+    let mut next_byte_range_offset = 0u64;
+    let mut media_sequence = 0u64;
+
+    for line in text.lines() {
+        let line = line.trim();
+
+        if let Some(value) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+            media_sequence = value.trim().parse().unwrap_or(0);
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-KEY:") {
+            current_key = parse_key_attrs(base_url, attrs, media_sequence);
+        } else if let Some(value) = line.strip_prefix("#EXT-X-BYTERANGE:") {
+            current_byte_range = parse_byte_range(value, next_byte_range_offset);
+        } else if !line.starts_with('#') && !line.is_empty() {
+            let url = resolve_url(base_url, line);
+
+            if let Some((start, end)) = current_byte_range {
+                next_byte_range_offset = end + 1;
+            }
+
+            segments.push(Segment {
+                url,
+                byte_range: current_byte_range.take(),
+                key: current_key.clone(),
+            });
+            media_sequence += 1;
+        }
+    }
+
+    Ok(segments)
+}
+
+fn parse_byte_range(value: &str, previous_end: u64) -> Option<(u64, u64)> {
+    let mut parts = value.splitn(2, '@');
+    let length: u64 = parts.next()?.trim().parse().ok()?;
+    let start = match parts.next() {
+        Some(offset) => offset.trim().parse().ok()?,
+        None => previous_end,
+    };
+    Some((start, start + length - 1))
+}
+
+fn parse_key_attrs(base_url: &str, attrs: &str, media_sequence: u64) -> Option<SegmentKey> {
+    let mut method = None;
+    let mut uri = None;
+    let mut iv_hex = None;
+
+    for pair in split_attribute_list(attrs) {
+        let (k, v) = pair.split_once('=')?;
+        match k.trim() {
+            "METHOD" => method = Some(v.trim().to_string()),
+            "URI" => uri = Some(v.trim().trim_matches('"').to_string()),
+            "IV" => iv_hex = Some(v.trim().to_string()),
+            _ => {}
+        }
+    }
+
+    if method.as_deref() == Some("NONE") {
+        return None;
+    }
+
+    let key_url = resolve_url(base_url, &uri?);
+
+    let iv = match iv_hex {
+        Some(hex) => parse_iv_hex(&hex).unwrap_or_else(|| media_sequence_iv(media_sequence)),
+        None => media_sequence_iv(media_sequence),
+    };
+
+    Some(SegmentKey { key_url, iv })
+}
+
+/// `#EXT-X-KEY` attribute lists are comma-separated, but a `URI="a,b"` value
+/// may itself contain commas, so split only outside quotes.
+pub(crate) fn split_attribute_list(attrs: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in attrs.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            ',' if !in_quotes => {
+                result.push(std::mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        result.push(current);
+    }
+    result
+}
+
+fn parse_iv_hex(value: &str) -> Option<[u8; 16]> {
+    let hex = value.trim().trim_start_matches("0x").trim_start_matches("0X");
+    if hex.len() != 32 {
+        return None;
+    }
+    let mut iv = [0u8; 16];
+    for i in 0..16 {
+        iv[i] = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(iv)
+}
+
+fn media_sequence_iv(media_sequence: u64) -> [u8; 16] {
+    let mut iv = [0u8; 16];
+    iv[8..].copy_from_slice(&media_sequence.to_be_bytes());
+    iv
+}
+
+pub(crate) fn resolve_url(base: &str, candidate: &str) -> String {
+    match Url::parse(base).and_then(|base_url| base_url.join(candidate)) {
+        Ok(resolved) => resolved.to_string(),
+        Err(e) => {
+            warn!("Could not resolve HLS URL {} against {}: {}", candidate, base, e);
+            candidate.to_string()
+        }
+    }
+}
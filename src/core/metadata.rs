@@ -11,9 +11,22 @@ pub struct VideoMetadata {
     pub upload_date: Option<String>,
     pub view_count: Option<u64>,
     pub like_count: Option<u64>,
+    /// The video's category (e.g. "Music", "Gaming"), from the player
+    /// response's microformat or, failing that, the JSON-LD `genre` field.
+    pub category: Option<String>,
+    /// Uploader-supplied keywords/tags, from `videoDetails.keywords`.
+    pub tags: Vec<String>,
     pub formats: Vec<VideoFormat>,
     pub thumbnails: Vec<Thumbnail>,
+    /// Manual (human-authored) subtitle tracks, keyed by language code.
     pub subtitles: HashMap<String, Vec<Subtitle>>,
+    /// Auto-generated (ASR) caption tracks, keyed by language code and kept
+    /// separate from `subtitles` since callers usually prefer manual ones.
+    pub automatic_captions: HashMap<String, Vec<Subtitle>>,
+    /// Language codes YouTube can translate captions into on the fly via a
+    /// subtitle URL's `tlang=` parameter.
+    pub translation_languages: Vec<String>,
+    pub chapters: Vec<Chapter>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +45,16 @@ pub struct VideoFormat {
     pub abr: Option<f64>, // audio bitrate
 }
 
+/// A playlist or channel listing, mirroring how `youtube_dl` models output as
+/// either a single video or a playlist of videos.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Playlist {
+    pub id: String,
+    pub title: String,
+    pub uploader: Option<String>,
+    pub entries: Vec<VideoMetadata>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Thumbnail {
     pub url: String,
@@ -46,3 +69,14 @@ pub struct Subtitle {
     pub ext: String,
     pub name: Option<String>,
 }
+
+/// A chapter marker, either from the player response's structured chapter
+/// data or parsed out of the description's timestamp lines. `end_time` is
+/// the next chapter's `start_time` (or the video's duration for the last
+/// one), filled in once the full list is known.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub start_time: f64,
+    pub end_time: Option<f64>,
+    pub title: String,
+}
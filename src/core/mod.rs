@@ -1,7 +1,11 @@
 pub mod downloader;
 pub mod extractor;
+pub mod format_sort;
+pub mod hls;
 pub mod metadata;
+pub mod rate_limiter;
 
-pub use downloader::Downloader;
-pub use extractor::{Extractor, ExtractorEngine};
-pub use metadata::{VideoMetadata, VideoFormat, Thumbnail};
\ No newline at end of file
+pub use downloader::{ClientType, Downloader, FormatSelection, StreamResolver, TlsBackend};
+pub use extractor::{ExtractionResult, Extractor, ExtractorEngine};
+pub use metadata::{Chapter, Playlist, Subtitle, VideoMetadata, VideoFormat, Thumbnail};
+pub use rate_limiter::RateLimiter;
\ No newline at end of file
@@ -0,0 +1,96 @@
+use anyhow::{bail, Result};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+/// A token bucket shared across concurrent downloads so total throughput
+/// stays under the configured `--rate-limit`, mirroring how upstream applies
+/// a rate limit across its HTTP/HLS/f4m downloaders.
+pub struct RateLimiter {
+    bytes_per_sec: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Fails if `bytes_per_sec` is `0` — a zero-rate bucket can never refill,
+    /// which would otherwise divide by zero in `acquire` and panic.
+    pub fn new(bytes_per_sec: u64) -> Result<Self> {
+        if bytes_per_sec == 0 {
+            bail!("rate limit must be greater than 0 bytes/sec");
+        }
+        Ok(Self {
+            bytes_per_sec: bytes_per_sec as f64,
+            state: Mutex::new(BucketState {
+                tokens: bytes_per_sec as f64,
+                last_refill: Instant::now(),
+            }),
+        })
+    }
+
+    /// Acquire `bytes` tokens, sleeping until the bucket has refilled enough
+    /// to cover them.
+    pub async fn acquire(&self, bytes: u64) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+                state.tokens = (state.tokens + elapsed * self.bytes_per_sec).min(self.bytes_per_sec);
+                state.last_refill = now;
+
+                if state.tokens >= bytes as f64 {
+                    state.tokens -= bytes as f64;
+                    None
+                } else {
+                    let deficit = bytes as f64 - state.tokens;
+                    state.tokens = 0.0;
+                    Some(Duration::from_secs_f64(deficit / self.bytes_per_sec))
+                }
+            };
+
+            match wait {
+                None => return,
+                Some(duration) => sleep(duration).await,
+            }
+        }
+    }
+}
+
+/// Parse a human rate-limit expression like `500K` or `4.2M` into bytes/sec.
+pub fn parse_rate(expr: &str) -> Option<u64> {
+    let expr = expr.trim();
+
+    let (number_part, multiplier) = if let Some(stripped) = expr.strip_suffix(['K', 'k']) {
+        (stripped, 1024.0)
+    } else if let Some(stripped) = expr.strip_suffix(['M', 'm']) {
+        (stripped, 1024.0 * 1024.0)
+    } else if let Some(stripped) = expr.strip_suffix(['G', 'g']) {
+        (stripped, 1024.0 * 1024.0 * 1024.0)
+    } else {
+        (expr, 1.0)
+    };
+
+    number_part
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|n| (n * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("500K"), Some(512_000));
+        assert_eq!(parse_rate("4.2M"), Some((4.2 * 1024.0 * 1024.0) as u64));
+        assert_eq!(parse_rate("1024"), Some(1024));
+        assert_eq!(parse_rate("bogus"), None);
+    }
+}
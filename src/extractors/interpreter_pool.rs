@@ -0,0 +1,65 @@
+use crate::extractors::js_interpreter::JSInterpreter;
+use anyhow::Result;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A small round-robin pool of independent `JSInterpreter`s, each holding its
+/// own copy of the player script. `JSInterpreter::decrypt_signature` spins up
+/// a fresh QuickJS runtime/context per call and is purely CPU-bound, so
+/// handing concurrent callers distinct interpreters (rather than funneling
+/// every signature/n-sig through the one interpreter `SignatureDecrypter`
+/// already owns) lets a batch of formats decrypt in parallel instead of
+/// serializing on a single `&mut self`. Sized to the machine's parallelism
+/// since that's the point at which adding more interpreters stops buying
+/// anything.
+pub struct InterpreterPool {
+    interpreters: Vec<Arc<JSInterpreter>>,
+    next: AtomicUsize,
+}
+
+impl InterpreterPool {
+    pub fn new(js_code: &str) -> Result<Self> {
+        let size = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+
+        let mut interpreters = Vec::with_capacity(size);
+        for _ in 0..size {
+            interpreters.push(Arc::new(JSInterpreter::new(js_code.to_string())?));
+        }
+
+        Ok(Self {
+            interpreters,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.interpreters.len()
+    }
+
+    /// Hand out the next interpreter in round-robin order.
+    pub fn checkout(&self) -> Arc<JSInterpreter> {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.interpreters.len();
+        Arc::clone(&self.interpreters[idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkout_round_robins_across_the_full_pool() {
+        let pool = InterpreterPool::new("function f(a) { return a; }").unwrap();
+        let size = pool.len();
+
+        // Checking out `size` times should visit every interpreter exactly
+        // once before any repeat, regardless of pool size.
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..size {
+            seen.insert(Arc::as_ptr(&pool.checkout()));
+        }
+        assert_eq!(seen.len(), size);
+    }
+}
@@ -1,8 +1,19 @@
 use anyhow::Result;
 use rquickjs::{Context, Runtime, Value, Array};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::debug;
 
+/// A primitive operation found in a decompiled signature-transform function.
+/// Applying these directly in Rust avoids spinning up a QuickJS runtime for
+/// player scripts we've already seen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SigOp {
+    Reverse,
+    Swap(usize),
+    Splice(usize),
+}
+
 /// JavaScript interpreter for YouTube signature decryption
 /// This uses rquickjs to execute the actual JavaScript signature functions
 pub struct JSInterpreter {
@@ -89,33 +100,40 @@ impl JSInterpreter {
         })
     }
     
-    /// Extract function code and arguments from JavaScript
+    /// Extract function code and arguments from JavaScript. `function_name`
+    /// is resolved by `find_signature_function_name`/`find_nsig_function_name`
+    /// against real YouTube player JS, which only ever matches assignment-style
+    /// declarations (`name=function(a){...}`), not named declarations
+    /// (`function name(a){...}`) — so both shapes have to be tried here.
     pub fn extract_function_code(&self, function_name: &str) -> Result<(Vec<String>, String)> {
-        // Use regex to find the function definition
-        let func_pattern = format!(r"function\s+{}\s*\([^)]*\)\s*\{{[^}}]*\}}", regex::escape(function_name));
-        let re = regex::Regex::new(&func_pattern)?;
-        
-        if let Some(captures) = re.find(&self.js_code) {
-            let func_code = captures.as_str();
-            
-            // Extract argument names
-            let args_pattern = format!(r"function\s+{}\s*\(([^)]*)\)", regex::escape(function_name));
-            let args_re = regex::Regex::new(&args_pattern)?;
-            
-            let args = if let Some(args_match) = args_re.captures(func_code) {
-                args_match.get(1).unwrap().as_str()
-                    .split(',')
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty())
-                    .collect()
-            } else {
-                vec![]
-            };
-            
-            Ok((args, func_code.to_string()))
-        } else {
-            anyhow::bail!("Could not find function {}", function_name)
+        let escaped_name = regex::escape(function_name);
+        let patterns = [
+            format!(r"function\s+{}\s*\([^)]*\)\s*\{{[^}}]*\}}", escaped_name),
+            format!(r"{}\s*=\s*function\s*\([^)]*\)\s*\{{[^}}]*\}}", escaped_name),
+        ];
+
+        for pattern in &patterns {
+            let re = regex::Regex::new(pattern)?;
+            if let Some(captures) = re.find(&self.js_code) {
+                let func_code = captures.as_str();
+
+                let args_re = regex::Regex::new(r"\(([^)]*)\)")?;
+
+                let args = if let Some(args_match) = args_re.captures(func_code) {
+                    args_match.get(1).unwrap().as_str()
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                } else {
+                    vec![]
+                };
+
+                return Ok((args, func_code.to_string()));
+            }
         }
+
+        anyhow::bail!("Could not find function {}", function_name)
     }
     
     /// Extract global variables from JavaScript code
@@ -148,6 +166,96 @@ impl JSInterpreter {
         Ok(globals)
     }
     
+    /// Decompile a signature function into a compact list of primitive
+    /// operations by following the helper-object method calls it invokes,
+    /// e.g. `Uc.ry(a,3)` where `Uc` is an object with `reverse`/`splice`/swap
+    /// methods. The result can be applied directly in Rust, skipping JS
+    /// evaluation entirely on a cache hit.
+    pub fn decompile_function(&self, function_name: &str) -> Result<Vec<SigOp>> {
+        let (_, func_code) = self.extract_function_code(function_name)?;
+
+        let call_re =
+            regex::Regex::new(r"([a-zA-Z_$][\w$]*)\.([a-zA-Z_$][\w$]*)\(\w+(?:,(\d+))?\)")?;
+
+        let helper_obj = call_re
+            .captures_iter(&func_code)
+            .next()
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str().to_string())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Could not find helper object in function {}", function_name)
+            })?;
+
+        let obj_pattern = format!(
+            r"var\s+{}\s*=\s*\{{([\s\S]*?)\}};",
+            regex::escape(&helper_obj)
+        );
+        let obj_body = regex::Regex::new(&obj_pattern)?
+            .captures(&self.js_code)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str().to_string())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Could not find helper object {} body", helper_obj)
+            })?;
+
+        let method_re = regex::Regex::new(r"([a-zA-Z_$][\w$]*):function\([^)]*\)\{([^}]*)\}")?;
+        let mut methods = HashMap::new();
+        for m in method_re.captures_iter(&obj_body) {
+            let name = m.get(1).unwrap().as_str().to_string();
+            let body = m.get(2).unwrap().as_str().to_string();
+            methods.insert(name, body);
+        }
+
+        let mut ops = Vec::new();
+        for call in call_re.captures_iter(&func_code) {
+            let method_name = call.get(2).unwrap().as_str();
+            let arg = call.get(3).and_then(|m| m.as_str().parse::<usize>().ok());
+
+            let body = match methods.get(method_name) {
+                Some(b) => b,
+                None => continue,
+            };
+
+            if body.contains("reverse") {
+                ops.push(SigOp::Reverse);
+            } else if body.contains("splice") {
+                ops.push(SigOp::Splice(arg.unwrap_or(0)));
+            } else {
+                // The `c=a[0];a[0]=a[b%a.len()];a[b]=c` swap idiom.
+                ops.push(SigOp::Swap(arg.unwrap_or(0)));
+            }
+        }
+
+        if ops.is_empty() {
+            anyhow::bail!("No operations decompiled for function {}", function_name);
+        }
+
+        Ok(ops)
+    }
+
+    /// Apply a decompiled op-list directly in Rust.
+    pub fn apply_ops(ops: &[SigOp], signature: &str) -> String {
+        let mut chars: Vec<char> = signature.chars().collect();
+
+        for op in ops {
+            match *op {
+                SigOp::Reverse => chars.reverse(),
+                SigOp::Swap(i) => {
+                    if !chars.is_empty() {
+                        let i = i % chars.len();
+                        chars.swap(0, i);
+                    }
+                }
+                SigOp::Splice(i) => {
+                    let i = i.min(chars.len());
+                    chars.drain(..i);
+                }
+            }
+        }
+
+        chars.into_iter().collect()
+    }
+
     /// Execute JavaScript code and return the result
     pub fn execute(&self, code: &str) -> Result<String> {
         let runtime = Runtime::new()?;
@@ -198,9 +306,26 @@ mod tests {
         let interpreter = JSInterpreter::new(js_code).unwrap();
         let result = interpreter.decrypt_signature("sig", "abcdef", None).unwrap();
         // Original: "abcdef"
-        // Reverse: "fedcba"  
+        // Reverse: "fedcba"
         // Splice at 1: "fdcba"
         // Swap 0 and 2: "cdcba"
         assert_eq!(result, "cdcba");
     }
+
+    #[test]
+    fn test_extract_function_code_assignment_style() {
+        // Real YouTube player JS declares the signature function as an
+        // assignment (`name=function(a){...}`), not a named declaration
+        // (`function name(a){...}`) — `find_signature_function_name` only
+        // ever resolves the former shape.
+        let js_code = r#"
+        var Uz={reverse:function(a){a.reverse()},splice:function(a,b){a.splice(0,b)}};
+        qJ=function(a){a=a.split("");Uz.reverse(a);Uz.splice(a,2);return a.join("")};
+        "#.to_string();
+
+        let interpreter = JSInterpreter::new(js_code).unwrap();
+        let (args, func_code) = interpreter.extract_function_code("qJ").unwrap();
+        assert_eq!(args, vec!["a"]);
+        assert!(func_code.starts_with("qJ=function"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,260 @@
+//! Best-effort parsers for the DASH MPD and HLS master playlists YouTube
+//! exposes via `streamingData.dashManifestUrl`/`hlsManifestUrl`, turned into
+//! `VideoFormat` entries the same way adaptive/regular formats are.
+
+use crate::core::hls::{resolve_url, split_attribute_list};
+use crate::core::VideoFormat;
+use regex::Regex;
+
+/// Parse an HLS master playlist's `#EXT-X-STREAM-INF` variants into one
+/// `VideoFormat` per rendition. The format's `url` stays a manifest/playlist
+/// URL (not a media segment), matching `core::hls::is_hls_format`'s
+/// expectation that the downloader resolves it further before fetching.
+pub fn parse_hls_master_playlist(playlist_url: &str, text: &str) -> Vec<VideoFormat> {
+    let mut formats = Vec::new();
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") else {
+            continue;
+        };
+        let Some(uri_line) = lines.peek() else {
+            continue;
+        };
+        if uri_line.starts_with('#') {
+            continue;
+        }
+
+        let bandwidth = find_attr(attrs, "BANDWIDTH").and_then(|v| v.parse::<u64>().ok());
+        let resolution = find_attr(attrs, "RESOLUTION");
+        let codecs = find_attr(attrs, "CODECS").map(|s| s.trim_matches('"').to_string());
+        let (vcodec, acodec) = split_codecs(codecs.as_deref());
+
+        let url = resolve_url(playlist_url, uri_line);
+
+        formats.push(VideoFormat {
+            format_id: format!("hls-{}", bandwidth.unwrap_or(0)),
+            url,
+            quality: None,
+            resolution,
+            fps: None,
+            vcodec,
+            acodec,
+            ext: "mp4".to_string(),
+            filesize: None,
+            tbr: bandwidth.map(|b| b as f64 / 1000.0),
+            vbr: None,
+            abr: None,
+        });
+    }
+
+    formats
+}
+
+/// Parse a DASH MPD's `Period`/`AdaptationSet`/`Representation` tree into one
+/// `VideoFormat` per representation, reading its `BaseURL` as the format URL.
+pub fn parse_dash_manifest(manifest_url: &str, xml: &str) -> Vec<VideoFormat> {
+    let mut formats = Vec::new();
+
+    let adaptation_set_re = Regex::new(r"(?s)<AdaptationSet\b([^>]*)>(.*?)</AdaptationSet>")
+        .expect("valid AdaptationSet regex");
+    let representation_re =
+        Regex::new(r"(?s)<Representation\b([^>]*?)(?:/>|>(.*?)</Representation>)")
+            .expect("valid Representation regex");
+    let base_url_re = Regex::new(r"<BaseURL>([^<]+)</BaseURL>").expect("valid BaseURL regex");
+
+    for adaptation_set in adaptation_set_re.captures_iter(xml) {
+        let adaptation_attrs = &adaptation_set[1];
+        let adaptation_body = &adaptation_set[2];
+        let adaptation_mime_type = find_xml_attr(adaptation_attrs, "mimeType");
+        let adaptation_base_url = base_url_re
+            .captures(adaptation_body)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str().trim().to_string());
+
+        for representation in representation_re.captures_iter(adaptation_body) {
+            let rep_attrs = &representation[1];
+            let rep_body = representation.get(2).map(|m| m.as_str()).unwrap_or("");
+
+            let id = find_xml_attr(rep_attrs, "id").unwrap_or_else(|| "0".to_string());
+            let bandwidth = find_xml_attr(rep_attrs, "bandwidth").and_then(|v| v.parse::<u64>().ok());
+            let codecs = find_xml_attr(rep_attrs, "codecs");
+            let width = find_xml_attr(rep_attrs, "width").and_then(|v| v.parse::<u32>().ok());
+            let height = find_xml_attr(rep_attrs, "height").and_then(|v| v.parse::<u32>().ok());
+            let fps = find_xml_attr(rep_attrs, "frameRate").and_then(|v| parse_frame_rate(&v));
+
+            let base_url = base_url_re
+                .captures(rep_body)
+                .and_then(|c| c.get(1))
+                .map(|m| m.as_str().trim().to_string())
+                .or_else(|| adaptation_base_url.clone());
+
+            let Some(base_url) = base_url else {
+                continue;
+            };
+            let url = resolve_url(manifest_url, &base_url);
+
+            let mime_type = adaptation_mime_type.clone().unwrap_or_default();
+            let (vcodec, acodec) = if mime_type.starts_with("video/") {
+                (codecs, None)
+            } else if mime_type.starts_with("audio/") {
+                (None, codecs)
+            } else {
+                (None, None)
+            };
+
+            let resolution = match (width, height) {
+                (Some(w), Some(h)) => Some(format!("{}x{}", w, h)),
+                _ => None,
+            };
+
+            let ext = if mime_type.contains("webm") { "webm" } else { "mp4" };
+
+            formats.push(VideoFormat {
+                format_id: format!("dash-{}", id),
+                url,
+                quality: None,
+                resolution,
+                fps,
+                vcodec,
+                acodec,
+                ext: ext.to_string(),
+                filesize: None,
+                tbr: bandwidth.map(|b| b as f64 / 1000.0),
+                vbr: None,
+                abr: None,
+            });
+        }
+    }
+
+    formats
+}
+
+/// Split an HLS `CODECS="avc1.64001f,mp4a.40.2"` value into a (video, audio)
+/// codec pair, best-effort.
+fn split_codecs(codecs: Option<&str>) -> (Option<String>, Option<String>) {
+    let Some(codecs) = codecs else {
+        return (None, None);
+    };
+
+    let mut vcodec = None;
+    let mut acodec = None;
+
+    for codec in codecs.split(',') {
+        let codec = codec.trim();
+        if codec.starts_with("mp4a") || codec.starts_with("opus") || codec.starts_with("ac-3") {
+            acodec = Some(codec.to_string());
+        } else if !codec.is_empty() {
+            vcodec = Some(codec.to_string());
+        }
+    }
+
+    (vcodec, acodec)
+}
+
+/// DASH `frameRate` attributes are either a plain integer or a `num/den`
+/// ratio, e.g. `30000/1001`.
+fn parse_frame_rate(value: &str) -> Option<f64> {
+    match value.split_once('/') {
+        Some((num, den)) => {
+            let num: f64 = num.parse().ok()?;
+            let den: f64 = den.parse().ok()?;
+            (den != 0.0).then_some(num / den)
+        }
+        None => value.parse().ok(),
+    }
+}
+
+/// Find an HLS `key="value"` attribute inside a comma-separated
+/// `#EXT-X-STREAM-INF:`-style attribute list, respecting quoting.
+fn find_attr(attrs: &str, key: &str) -> Option<String> {
+    for pair in split_attribute_list(attrs) {
+        let (k, v) = pair.split_once('=')?;
+        if k.trim() == key {
+            return Some(v.trim().trim_matches('"').to_string());
+        }
+    }
+    None
+}
+
+/// Find an XML `key="value"` attribute inside a DASH `<Representation ...>`
+/// (or `<AdaptationSet ...>`) start tag's attribute string. Unlike HLS's
+/// attribute lists, XML attributes are whitespace-separated rather than
+/// comma-separated, so this can't reuse `split_attribute_list`.
+fn find_xml_attr(attrs: &str, key: &str) -> Option<String> {
+    for pair in split_xml_attribute_list(attrs) {
+        let (k, v) = pair.split_once('=')?;
+        if k.trim() == key {
+            return Some(v.trim().trim_matches('"').to_string());
+        }
+    }
+    None
+}
+
+/// Split an XML start tag's attribute string on whitespace, respecting
+/// quoted values (an attribute value can itself contain spaces, e.g.
+/// `codecs="avc1.640028, mp4a.40.2"`).
+fn split_xml_attribute_list(attrs: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in attrs.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    result.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        result.push(current);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hls_master_playlist() {
+        let playlist = "#EXTM3U\n\
+#EXT-X-STREAM-INF:BANDWIDTH=1280000,RESOLUTION=1920x1080,CODECS=\"avc1.640028,mp4a.40.2\"\n\
+https://example.com/hls/1080p.m3u8\n";
+
+        let formats = parse_hls_master_playlist("https://example.com/hls/master.m3u8", playlist);
+        assert_eq!(formats.len(), 1);
+        assert_eq!(formats[0].format_id, "hls-1280000");
+        assert_eq!(formats[0].url, "https://example.com/hls/1080p.m3u8");
+        assert_eq!(formats[0].resolution.as_deref(), Some("1920x1080"));
+        assert_eq!(formats[0].vcodec.as_deref(), Some("avc1.640028"));
+        assert_eq!(formats[0].acodec.as_deref(), Some("mp4a.40.2"));
+    }
+
+    #[test]
+    fn test_parse_dash_manifest() {
+        let mpd = r#"<MPD><Period>
+<AdaptationSet mimeType="video/mp4">
+<Representation id="137" bandwidth="2000000" codecs="avc1.640028" width="1920" height="1080" frameRate="30">
+<BaseURL>https://example.com/dash/137.mp4</BaseURL>
+</Representation>
+</AdaptationSet>
+</Period></MPD>"#;
+
+        let formats = parse_dash_manifest("https://example.com/dash/manifest.mpd", mpd);
+        assert_eq!(formats.len(), 1);
+        assert_eq!(formats[0].format_id, "dash-137");
+        assert_eq!(formats[0].url, "https://example.com/dash/137.mp4");
+        assert_eq!(formats[0].resolution.as_deref(), Some("1920x1080"));
+        assert_eq!(formats[0].vcodec.as_deref(), Some("avc1.640028"));
+        assert_eq!(formats[0].acodec, None);
+        assert_eq!(formats[0].fps, Some(30.0));
+    }
+}
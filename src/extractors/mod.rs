@@ -0,0 +1,8 @@
+pub mod interpreter_pool;
+pub mod js_interpreter;
+pub mod manifest;
+pub mod player_cache;
+pub mod youtube;
+pub mod youtube_signature;
+
+pub use youtube::YouTubeExtractor;
@@ -0,0 +1,136 @@
+use crate::extractors::js_interpreter::SigOp;
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Persistent cache of decompiled signature-transform op-lists, keyed by
+/// player version, stored under `$XDG_CACHE_HOME/yt-dlp-ng/` like upstream
+/// yt-dlp's player-file cache. Consulted before downloading/evaluating any
+/// player JavaScript.
+pub struct PlayerCache {
+    dir: PathBuf,
+}
+
+impl PlayerCache {
+    pub fn new() -> Result<Self> {
+        let dir = cache_home().join("yt-dlp-ng");
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn get(&self, player_version: &str) -> Option<Vec<SigOp>> {
+        let data = std::fs::read_to_string(self.path_for(player_version)).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    pub fn put(&self, player_version: &str, ops: &[SigOp]) -> Result<()> {
+        let data = serde_json::to_string(ops)?;
+        std::fs::write(self.path_for(player_version), data)?;
+        Ok(())
+    }
+
+    /// Remove every cached op-list, forcing the next decrypt of each player
+    /// to re-derive (and re-persist) its transform from scratch.
+    pub fn clear(&self) -> Result<()> {
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn path_for(&self, player_version: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", player_version))
+    }
+}
+
+/// Derive a short "signature shape" id from an example signature by joining
+/// the length of each dot-separated part, e.g. `"86.32.10"`. Mirrors
+/// upstream yt-dlp's `_signature_cache_id`: two signatures that happen to
+/// come from player builds sharing a version id but differing in shape
+/// shouldn't be able to reuse each other's cached op list.
+pub fn signature_cache_id(example_sig: &str) -> String {
+    example_sig
+        .split('.')
+        .map(|part| part.len().to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn cache_home() -> PathBuf {
+    if let Some(xdg) = std::env::var_os("XDG_CACHE_HOME") {
+        return PathBuf::from(xdg);
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        return PathBuf::from(home).join(".cache");
+    }
+    PathBuf::from(".cache")
+}
+
+/// Parse the player version out of a player JS URL, e.g.
+/// `/s/player/64dddad6/player_ias.vflset/en_US/base.js` -> `64dddad6`.
+pub fn player_version_from_url(url: &str) -> Option<String> {
+    let re = regex::Regex::new(r"/s/player/([a-zA-Z0-9_-]+)/").ok()?;
+    re.captures(url)?.get(1).map(|m| m.as_str().to_string())
+}
+
+/// Parse the short 8-hex-character player id out of a player JS URL, e.g.
+/// `/s/player/64dddad6/player_ias.vflset/en_US/base.js` -> `64dddad6`. Unlike
+/// `player_version_from_url`, this only matches the classic hex id shape
+/// (not the longer alphanumeric ids some experimental player builds use),
+/// which is what `PlayerInfo` caching keys on.
+pub fn extract_player_id(js_url: &str) -> Option<String> {
+    let re = regex::Regex::new(r"/s/player/([0-9a-f]{8})").ok()?;
+    re.captures(js_url)?.get(1).map(|m| m.as_str().to_string())
+}
+
+/// Parse the `signatureTimestamp` (`sts`) value out of player JS, needed in
+/// the InnerTube player API's `playbackContext` to get back signature URLs
+/// that validate against that exact player build.
+pub fn extract_signature_timestamp(js_content: &str) -> Option<u64> {
+    let re = regex::Regex::new(r"signatureTimestamp[=:](\d+)").ok()?;
+    re.captures(js_content)?.get(1)?.as_str().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_player_version_from_url() {
+        assert_eq!(
+            player_version_from_url("https://www.youtube.com/s/player/64dddad6/player_ias.vflset/en_US/base.js"),
+            Some("64dddad6".to_string())
+        );
+        assert_eq!(player_version_from_url("https://example.com/not-a-player.js"), None);
+    }
+
+    #[test]
+    fn test_signature_cache_id() {
+        assert_eq!(signature_cache_id("abcde.fg.hijklmno"), "5.2.8");
+        assert_eq!(signature_cache_id("nodots"), "6");
+    }
+
+    #[test]
+    fn test_extract_player_id() {
+        assert_eq!(
+            extract_player_id("https://www.youtube.com/s/player/64dddad6/player_ias.vflset/en_US/base.js"),
+            Some("64dddad6".to_string())
+        );
+        assert_eq!(extract_player_id("https://example.com/not-a-player.js"), None);
+    }
+
+    #[test]
+    fn test_extract_signature_timestamp() {
+        assert_eq!(
+            extract_signature_timestamp("some code...signatureTimestamp=19834...more code"),
+            Some(19834)
+        );
+        assert_eq!(
+            extract_signature_timestamp("var ytcfg={signatureTimestamp:19834};"),
+            Some(19834)
+        );
+        assert_eq!(extract_signature_timestamp("no timestamp here"), None);
+    }
+}
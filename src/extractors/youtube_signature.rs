@@ -1,13 +1,54 @@
 use anyhow::Result;
 use regex::Regex;
 use std::collections::HashMap;
+use std::sync::Arc;
+use crate::extractors::interpreter_pool::InterpreterPool;
 use crate::extractors::js_interpreter::JSInterpreter;
+use crate::extractors::player_cache::{
+    extract_player_id, extract_signature_timestamp, player_version_from_url, signature_cache_id,
+    PlayerCache,
+};
+
+/// Everything worth keeping from a player script once it's been located and
+/// parsed, so a later video that happens to share the same player build can
+/// skip straight to decryption instead of re-running every extraction regex.
+/// Keyed by `player_id` (the short 8-hex id in the player JS URL) in
+/// `SignatureDecrypter::player_info_cache`.
+#[derive(Debug, Clone)]
+pub struct PlayerInfo {
+    pub player_id: String,
+    /// Name of the classic signature-cipher function, if one was found.
+    pub sig_function_name: Option<String>,
+    /// Name of the n-sig (throttling) function, if one was found.
+    pub nsig_function_name: Option<String>,
+    /// Self-contained `var <name>=function...;` source for the n-sig
+    /// function, ready to hand to a fresh `JSInterpreter` without the rest
+    /// of the player script around it.
+    pub nsig_function_source: Option<String>,
+    /// The `signatureTimestamp` (`sts`) this player build expects echoed
+    /// back in the InnerTube player API's `playbackContext`.
+    pub signature_timestamp: Option<u64>,
+}
 
 /// YouTube signature decryption based on yt-dlp's approach
 /// This implementation uses rquickjs to execute actual JavaScript signature functions
 pub struct SignatureDecrypter {
+    /// Completed, ordered transform plans for the pattern-based fallback
+    /// cipher, keyed by player id so a second video sharing the same player
+    /// build skips re-deriving the plan from regex extraction.
     transform_cache: HashMap<String, Vec<TransformOp>>,
     js_interpreter: Option<JSInterpreter>,
+    /// A pool of standalone interpreters over the same player script as
+    /// `js_interpreter`, used by `decrypt_many` to decrypt a batch of
+    /// signatures concurrently instead of serially.
+    interpreter_pool: Option<Arc<InterpreterPool>>,
+    player_cache: Option<PlayerCache>,
+    player_version: Option<String>,
+    /// Resolved `PlayerInfo` per player id, so a second video using the same
+    /// player build skips re-extracting its signature/n-sig functions.
+    player_info_cache: HashMap<String, PlayerInfo>,
+    /// Player id of the script most recently loaded via `init_js_interpreter`.
+    current_player_id: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -15,6 +56,20 @@ enum TransformOp {
     Reverse,
     Splice(usize),
     Swap(usize),
+    /// JS `a.slice(n)` / `a.splice(0,n)`: drop the first `n` characters.
+    Slice(usize),
+}
+
+/// Classification of a transform-object method body into an operation kind,
+/// independent of the numeric argument a given call site passes it (the
+/// same method is typically called several times in one signature function,
+/// each time with a different index).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MethodKind {
+    Reverse,
+    Splice,
+    Swap,
+    Slice,
 }
 
 impl SignatureDecrypter {
@@ -22,27 +77,120 @@ impl SignatureDecrypter {
         Self {
             transform_cache: HashMap::new(),
             js_interpreter: None,
+            interpreter_pool: None,
+            player_cache: PlayerCache::new().ok(),
+            player_version: None,
+            player_info_cache: HashMap::new(),
+            current_player_id: None,
         }
     }
-    
-    /// Initialize the JavaScript interpreter with player code
-    pub fn init_js_interpreter(&mut self, js_code: String) -> Result<()> {
+
+    /// Initialize the JavaScript interpreter with player code. `js_url`, when
+    /// available, is used to key the persistent op-list cache on the player
+    /// version so repeat extractions can skip JS evaluation entirely. Also
+    /// builds the `InterpreterPool` `decrypt_many` draws from, and resolves
+    /// (or reuses, on a player id already seen) this build's `PlayerInfo`.
+    pub fn init_js_interpreter(&mut self, js_code: String, js_url: Option<&str>) -> Result<()> {
+        self.player_version = js_url.and_then(player_version_from_url);
+        self.interpreter_pool = Some(Arc::new(InterpreterPool::new(&js_code)?));
+
+        let player_id = js_url.and_then(extract_player_id);
+        if let Some(player_id) = &player_id {
+            if !self.player_info_cache.contains_key(player_id) {
+                tracing::debug!("Resolving PlayerInfo for player {}", player_id);
+                let sig_function_name = self.find_signature_function_name(&js_code).ok();
+                let nsig_source = Self::find_nsig_function_source(&js_code);
+                let nsig_function_name = nsig_source.as_ref().map(|(name, _)| name.clone());
+                let nsig_function_source = nsig_source.map(|(_, source)| source);
+                let signature_timestamp = extract_signature_timestamp(&js_code);
+
+                self.player_info_cache.insert(
+                    player_id.clone(),
+                    PlayerInfo {
+                        player_id: player_id.clone(),
+                        sig_function_name,
+                        nsig_function_name,
+                        nsig_function_source,
+                        signature_timestamp,
+                    },
+                );
+            }
+        }
+        self.current_player_id = player_id;
+
         let interpreter = JSInterpreter::new(js_code)?;
         self.js_interpreter = Some(interpreter);
         Ok(())
     }
 
+    /// `PlayerInfo` resolved for the player script most recently loaded via
+    /// `init_js_interpreter`, if its URL carried a recognizable player id.
+    pub fn current_player_info(&self) -> Option<&PlayerInfo> {
+        self.current_player_id.as_ref().and_then(|id| self.player_info_cache.get(id))
+    }
+
+    /// Build the persistent cache key for a transform: the player version
+    /// plus a `signature_cache_id` fingerprint of `example`'s shape, so two
+    /// differently-shaped signatures/n-values under the same player version
+    /// never share a cached op list. `suffix` disambiguates the n-sig cache
+    /// from the classic signature cache.
+    fn cache_key(&self, example: &str, suffix: &str) -> Option<String> {
+        let version = self.player_version.as_ref()?;
+        Some(format!("{}-{}{}", version, signature_cache_id(example), suffix))
+    }
+
+    /// Drop every persisted op list, e.g. after a player update is
+    /// suspected of invalidating cached transforms.
+    pub fn clear_cache(&self) -> Result<()> {
+        if let Some(cache) = &self.player_cache {
+            cache.clear()?;
+        }
+        Ok(())
+    }
+
     pub fn decrypt_signature(&mut self, signature: &str, js_content: &str) -> Result<String> {
+        let cache_key = self.cache_key(signature, "");
+
+        // Consult the persistent op-list cache before touching QuickJS at all.
+        if let (Some(cache), Some(key)) = (&self.player_cache, &cache_key) {
+            if let Some(ops) = cache.get(key) {
+                tracing::debug!("Using cached signature ops for player {}", key);
+                return Ok(JSInterpreter::apply_ops(&ops, signature));
+            }
+        }
+
+        // Prefer the cached PlayerInfo's function name, if this player build
+        // has already been resolved once, over re-running the name-finding
+        // regexes against `js_content` again.
+        let function_name = self
+            .current_player_info()
+            .and_then(|info| info.sig_function_name.clone())
+            .or_else(|| self.find_signature_function_name(js_content).ok());
+
         // Try to use JavaScript interpreter first
         if let Some(ref interpreter) = self.js_interpreter {
-            if let Ok(function_name) = self.find_signature_function_name(js_content) {
+            if let Some(function_name) = function_name {
                 // Extract global variables
                 let globals = interpreter.extract_global_vars().unwrap_or_default();
-                
+
                 // Try to execute the actual signature function
                 match interpreter.decrypt_signature(&function_name, signature, Some(globals)) {
                     Ok(result) => {
                         tracing::debug!("JavaScript signature decryption successful: {} -> {}", signature, result);
+
+                        // Decompile and persist the op-list so the next call
+                        // (even across process runs) can skip the runtime.
+                        if let (Some(cache), Some(key)) = (&self.player_cache, &cache_key) {
+                            match interpreter.decompile_function(&function_name) {
+                                Ok(ops) => {
+                                    if let Err(e) = cache.put(key, &ops) {
+                                        tracing::warn!("Failed to persist signature op cache: {}", e);
+                                    }
+                                }
+                                Err(e) => tracing::debug!("Could not decompile signature function: {}", e),
+                            }
+                        }
+
                         return Ok(result);
                     }
                     Err(e) => {
@@ -52,10 +200,11 @@ impl SignatureDecrypter {
                 }
             }
         }
-        
+
         // Fallback to pattern-based signature decryption
         tracing::debug!("Using fallback pattern-based signature decryption");
-        let operations = self.extract_signature_operations(js_content)?;
+        let player_id = self.current_player_id.clone();
+        let operations = self.extract_signature_plan(js_content, player_id.as_deref())?;
 
         // Apply operations to the signature
         let mut sig_chars: Vec<char> = signature.chars().collect();
@@ -71,72 +220,340 @@ impl SignatureDecrypter {
                     }
                 }
                 TransformOp::Swap(index) => {
-                    if index < sig_chars.len() {
-                        sig_chars.swap(0, index);
+                    let len = sig_chars.len();
+                    if len > 0 {
+                        let target = index % len;
+                        sig_chars.swap(0, target);
                     }
                 }
+                TransformOp::Slice(count) => {
+                    let count = count.min(sig_chars.len());
+                    sig_chars.drain(..count);
+                }
             }
         }
 
         Ok(sig_chars.into_iter().collect())
     }
 
-    /// Decrypt the n-sig parameter to prevent throttling
-    /// This is critical for working YouTube downloads
-    pub fn decrypt_nsig(&mut self, nsig: &str, js_content: &str) -> Result<String> {
-        // Try to use JavaScript interpreter for n-sig decryption
-        if let Some(ref interpreter) = self.js_interpreter {
-            // Look for n-sig function patterns
-            let nsig_patterns = [
-                r"([a-zA-Z_$][a-zA-Z0-9_$]*)\s*=\s*function\s*\([^)]*\)\s*\{[^}]*\.get\([^)]*\)\s*\&\&[^}]*\}",
-                r"([a-zA-Z_$][a-zA-Z0-9_$]*)\s*=\s*function\s*\([^)]*\)\s*\{.*?enhanced_except.*?\}",
-                r#"([a-zA-Z_$][a-zA-Z0-9_$]*)\s*=\s*function\s*\([^)]*\)\s*\{.*?\.join\(\s*""\s*\).*?\}"#,
-            ];
-            
-            for pattern in &nsig_patterns {
-                if let Ok(re) = Regex::new(pattern) {
-                    if let Some(captures) = re.captures(js_content) {
-                        if let Some(func_name) = captures.get(1) {
-                            let function_name = func_name.as_str();
-                            
-                            // Extract global variables
-                            let globals = interpreter.extract_global_vars().unwrap_or_default();
-                            
-                            // Try to execute the n-sig function
-                            match interpreter.decrypt_signature(function_name, nsig, Some(globals)) {
-                                Ok(result) => {
-                                    tracing::debug!("JavaScript n-sig decryption successful: {} -> {}", nsig, result);
-                                    return Ok(result);
-                                }
-                                Err(e) => {
-                                    tracing::warn!("JavaScript n-sig decryption failed for {}: {}", function_name, e);
+    /// Decrypt a batch of classic signatures concurrently, using the
+    /// `InterpreterPool` built by `init_js_interpreter` so each signature
+    /// runs on its own QuickJS context instead of serializing through one
+    /// shared interpreter. Falls back to decrypting serially through
+    /// `decrypt_signature` when no pool is available (e.g. before
+    /// `init_js_interpreter` has run). Results are returned in the same
+    /// order as `sigs`.
+    pub fn decrypt_many(&mut self, sigs: &[String], js_content: &str) -> Vec<Result<String>> {
+        let Some(pool) = self.interpreter_pool.clone() else {
+            return sigs.iter().map(|sig| self.decrypt_signature(sig, js_content)).collect();
+        };
+
+        let function_name = match self.find_signature_function_name(js_content) {
+            Ok(name) => name,
+            Err(e) => return sigs.iter().map(|_| Err(anyhow::anyhow!("{}", e))).collect(),
+        };
+
+        let runtime_handle = tokio::runtime::Handle::current();
+
+        tokio::task::block_in_place(|| {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = sigs
+                    .iter()
+                    .map(|sig| {
+                        let pool = Arc::clone(&pool);
+                        let function_name = function_name.clone();
+                        let runtime_handle = runtime_handle.clone();
+                        scope.spawn(move || {
+                            let _guard = runtime_handle.enter();
+                            let interpreter = pool.checkout();
+                            interpreter.decrypt_signature(&function_name, sig, None)
+                        })
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap_or_else(|_| Err(anyhow::anyhow!("n-sig decryption thread panicked"))))
+                    .collect()
+            })
+        })
+    }
+
+    /// Decrypt the n-sig parameter to prevent throttling. Consults the same
+    /// persistent per-player cache as `decrypt_signature` (under a distinct
+    /// `-nsig` key) so a batch of formats from one video only has to locate
+    /// and decompile the n-sig function once, not once per format.
+    ///
+    /// Unlike `decrypt_signature`, which looks up its function by name inside
+    /// the interpreter already holding the whole player script, this locates
+    /// the n-sig function's own source with `find_nsig_function_source` and
+    /// evaluates that small, self-contained snippet in a fresh `JSInterpreter`
+    /// of its own. The n-sig function is reached via an array of candidate
+    /// identifiers in modern player builds rather than a name that appears
+    /// directly at the call site, which the old name-only regexes couldn't
+    /// follow.
+    pub fn decrypt_nsig(&self, nsig: &str, js_content: &str) -> Result<String> {
+        let cache_key = self.cache_key(nsig, "-nsig");
+
+        if let (Some(cache), Some(key)) = (&self.player_cache, &cache_key) {
+            if let Some(ops) = cache.get(key) {
+                tracing::debug!("Using cached n-sig ops for player {}", key);
+                return Ok(Self::check_nsig_result(nsig, JSInterpreter::apply_ops(&ops, nsig)));
+            }
+        }
+
+        // Prefer the cached PlayerInfo's extracted source over re-running
+        // the array-index/function-ending extraction against `js_content`.
+        let nsig_source = self
+            .current_player_info()
+            .and_then(|info| Some((info.nsig_function_name.clone()?, info.nsig_function_source.clone()?)))
+            .or_else(|| Self::find_nsig_function_source(js_content));
+
+        if let Some((function_name, source)) = nsig_source {
+            match JSInterpreter::new(source) {
+                Ok(interpreter) => match interpreter.decrypt_signature(&function_name, nsig, None) {
+                    Ok(result) => {
+                        tracing::debug!("JavaScript n-sig decryption successful: {} -> {}", nsig, result);
+
+                        if let (Some(cache), Some(key)) = (&self.player_cache, &cache_key) {
+                            match interpreter.decompile_function(&function_name) {
+                                Ok(ops) => {
+                                    if let Err(e) = cache.put(key, &ops) {
+                                        tracing::warn!("Failed to persist n-sig op cache: {}", e);
+                                    }
                                 }
+                                Err(e) => tracing::debug!("Could not decompile n-sig function: {}", e),
                             }
                         }
+
+                        return Ok(Self::check_nsig_result(nsig, result));
+                    }
+                    Err(e) => {
+                        tracing::warn!("JavaScript n-sig decryption failed for {}: {}", function_name, e);
                     }
+                },
+                Err(e) => {
+                    tracing::warn!("Failed to build standalone n-sig interpreter for {}: {}", function_name, e);
                 }
             }
+        } else {
+            tracing::debug!("Could not locate n-sig function via array-index/function-ending technique");
         }
-        
+
         // Fallback: just return the original n-sig
         tracing::debug!("n-sig passthrough: {}", nsig);
         Ok(nsig.to_string())
     }
 
-    fn extract_signature_operations(&mut self, js_content: &str) -> Result<Vec<TransformOp>> {
-        // This is a simplified version of yt-dlp's signature extraction
-        // In reality, yt-dlp has much more sophisticated JS parsing
+    /// Stage one of n-sig function location: scan for one of the "array
+    /// reference" call-site shapes modern player builds use to invoke the
+    /// n-sig transform, e.g. `null)&&(b=NFUNC[3](c))` or the `"n+"`-prefixed
+    /// variant. When a numeric index was captured alongside the array
+    /// identifier, resolve the real function name by finding that array's
+    /// literal (`var NFUNC=[a,b,c,...]`) and indexing into it; otherwise the
+    /// capture is already the function name.
+    fn find_nsig_function_name(js_content: &str) -> Option<String> {
+        let array_ref_patterns = [
+            r"null\)&&\([a-zA-Z]=(?P<nfunc>[a-zA-Z0-9$]+)\[(?P<idx>\d+)\]\([a-zA-Z0-9]\)",
+            r#"&&\(b="n\+"\[[a-zA-Z0-9.+$]+\],c=a\.get\(b\)\)&&\(c=(?P<nfunc>[a-zA-Z0-9$]+)(?:\[(?P<idx>\d+)\])?\([a-zA-Z0-9]\)"#,
+        ];
+
+        for pattern in &array_ref_patterns {
+            let re = Regex::new(pattern).ok()?;
+            if let Some(captures) = re.captures(js_content) {
+                let nfunc = captures.name("nfunc")?.as_str();
+                return match captures.name("idx") {
+                    Some(idx_match) => {
+                        let idx: usize = idx_match.as_str().parse().ok()?;
+                        Self::resolve_array_element(js_content, nfunc, idx)
+                    }
+                    None => Some(nfunc.to_string()),
+                };
+            }
+        }
+
+        None
+    }
+
+    /// Look up the `idx`-th element of an array literal `var NAME=[a,b,c];`
+    /// declared somewhere in the player script.
+    fn resolve_array_element(js_content: &str, array_name: &str, idx: usize) -> Option<String> {
+        let pattern = format!(r"var\s+{}\s*=\s*\[([^\]]*)\]", regex::escape(array_name));
+        let re = Regex::new(&pattern).ok()?;
+        let elements = re.captures(js_content)?.get(1)?.as_str();
+        elements.split(',').nth(idx).map(|el| el.trim().to_string())
+    }
+
+    /// Stage two of n-sig function location: given the function's name,
+    /// extract its full source as a `<name>=function(...){...}` assignment
+    /// (the form the function body actually appears in, whether or not it
+    /// was reached through the indirection stage one resolved) and wrap it
+    /// as a standalone `var <name>=function...;` snippet, ready to be handed
+    /// to a fresh `JSInterpreter` without needing the rest of the player
+    /// script around it.
+    fn find_nsig_function_source(js_content: &str) -> Option<(String, String)> {
+        let function_name = Self::find_nsig_function_name(js_content)?;
+        let escaped = regex::escape(&function_name);
+        let body_patterns = [
+            format!(r#"{}=\s*function([\S\s]*?\}}\s*return \w+?\.join\(""\)\s*\}};)"#, escaped),
+            format!(r#"{}=\s*function([\S\s]*?\.call\([^)]*,\s*""\s*\)\s*\}};)"#, escaped),
+        ];
+
+        for pattern in &body_patterns {
+            let re = Regex::new(pattern).ok()?;
+            if let Some(captures) = re.captures(js_content) {
+                let body = captures.get(1)?.as_str();
+                let source = format!("var {}=function{}", function_name, body);
+                return Some((function_name, source));
+            }
+        }
+
+        None
+    }
+
+    /// YouTube's n-sig function returns a value starting with
+    /// `enhanced_except` (or occasionally the untouched input) when it
+    /// detects it's being run outside a real browser. Shipping that value
+    /// verbatim throttles the download just as badly as never transforming
+    /// `n` at all, so treat it the same as a failed transform and fall back
+    /// to the original, logging a warning either way.
+    fn check_nsig_result(nsig: &str, result: String) -> String {
+        if result.starts_with("enhanced_except") || result == nsig {
+            tracing::warn!("n-sig transform produced an untransformed/placeholder result; using original: {}", nsig);
+            return nsig.to_string();
+        }
+        result
+    }
+
+    /// Derive (or reuse, from `transform_cache`) the ordered list of cipher
+    /// operations the signature function applies, by extracting the call
+    /// order out of the function body and classifying each call's method
+    /// name against the transform object's own method bodies. Unlike the
+    /// old guess-one-operation-per-method-definition approach, this respects
+    /// both the order and repetition of calls (the same method is commonly
+    /// invoked several times with different indices) and fails loudly,
+    /// rather than falling back to a hardcoded guess, when no plan can be
+    /// derived.
+    fn extract_signature_plan(
+        &mut self,
+        js_content: &str,
+        player_id: Option<&str>,
+    ) -> Result<Vec<TransformOp>> {
+        if let Some(id) = player_id {
+            if let Some(ops) = self.transform_cache.get(id) {
+                tracing::debug!("Using cached transform plan for player {}", id);
+                return Ok(ops.clone());
+            }
+        }
 
-        // Find the signature function
         let sig_func_name = self.find_signature_function_name(js_content)?;
+        if sig_func_name == "dummyFunction" {
+            anyhow::bail!("could not locate the signature function to build a transform plan");
+        }
 
-        // Extract the transform object name
         let transform_obj_name = self.find_transform_object_name(js_content, &sig_func_name)?;
+        if transform_obj_name == "dummyObject" {
+            anyhow::bail!("could not locate the transform object to build a transform plan");
+        }
 
-        // Extract the operations from the transform object
-        let operations = self.extract_transform_operations(js_content, &transform_obj_name)?;
+        let func_body = Self::find_function_body(js_content, &sig_func_name).ok_or_else(|| {
+            anyhow::anyhow!("could not locate body of signature function {}", sig_func_name)
+        })?;
 
-        Ok(operations)
+        let method_kinds = Self::classify_transform_methods(js_content, &transform_obj_name)?;
+
+        let call_re = Regex::new(r"\w+\.(\w+)\(\w,(\d+)\)")?;
+        let mut plan = Vec::new();
+        for call in call_re.captures_iter(&func_body) {
+            let method = &call[1];
+            let arg: usize = call[2].parse().unwrap_or(0);
+
+            match method_kinds.get(method) {
+                Some(MethodKind::Reverse) => plan.push(TransformOp::Reverse),
+                Some(MethodKind::Splice) => plan.push(TransformOp::Splice(arg)),
+                Some(MethodKind::Swap) => plan.push(TransformOp::Swap(arg)),
+                Some(MethodKind::Slice) => plan.push(TransformOp::Slice(arg)),
+                None => tracing::debug!("Unrecognized transform method in plan: {}", method),
+            }
+        }
+
+        if plan.is_empty() {
+            anyhow::bail!("derived an empty signature transform plan from {}", transform_obj_name);
+        }
+
+        tracing::debug!("Derived {}-step transform plan from {}", plan.len(), transform_obj_name);
+
+        if let Some(id) = player_id {
+            self.transform_cache.insert(id.to_string(), plan.clone());
+        }
+
+        Ok(plan)
+    }
+
+    /// Extract the full `{ ... }` body of a `name=function(...){...}`
+    /// definition. Like the rest of this module's extraction, this assumes
+    /// a minified, brace-free-inside-the-body function, which holds for the
+    /// signature functions seen in practice.
+    fn find_function_body(js_content: &str, func_name: &str) -> Option<String> {
+        let pattern = format!(r#"{}=function\([^)]*\)\{{([^}}]+)\}}"#, regex::escape(func_name));
+        let re = Regex::new(&pattern).ok()?;
+        re.captures(js_content)?.get(1).map(|m| m.as_str().to_string())
+    }
+
+    /// Classify every method on the transform object into a `MethodKind`,
+    /// keyed by method name, independent of the numeric argument any
+    /// particular call site passes it.
+    fn classify_transform_methods(
+        js_content: &str,
+        transform_obj_name: &str,
+    ) -> Result<HashMap<String, MethodKind>> {
+        let mut kinds = HashMap::new();
+
+        let obj_patterns = [
+            format!(r#"var\s+{}\s*=\s*\{{([^}}]+)\}}"#, regex::escape(transform_obj_name)),
+            format!(r#"{}\s*=\s*\{{([^}}]+)\}}"#, regex::escape(transform_obj_name)),
+            format!(r#"const\s+{}\s*=\s*\{{([^}}]+)\}}"#, regex::escape(transform_obj_name)),
+            format!(r#"let\s+{}\s*=\s*\{{([^}}]+)\}}"#, regex::escape(transform_obj_name)),
+        ];
+
+        for obj_pattern in &obj_patterns {
+            let re = Regex::new(obj_pattern)?;
+            if let Some(captures) = re.captures(js_content) {
+                if let Some(obj_body) = captures.get(1) {
+                    let method_re = Regex::new(r#"([a-zA-Z_\$][\w\$]*):function\([^)]*\)\{([^}]+)\}"#)?;
+
+                    for method_match in method_re.captures_iter(obj_body.as_str()) {
+                        if let (Some(name), Some(body)) = (method_match.get(1), method_match.get(2)) {
+                            if let Some(kind) = Self::classify_method_kind(body.as_str()) {
+                                kinds.insert(name.as_str().to_string(), kind);
+                            }
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+
+        Ok(kinds)
+    }
+
+    /// Classify a single transform-object method body by the operation it
+    /// performs: `a.splice(0,b)` drops the leading `b` characters (`Slice`),
+    /// `a.splice(b,1)` (or any other `splice` shape) removes one character
+    /// at `b` (`Splice`), the `var c=a[0];a[0]=a[b...` idiom swaps index 0
+    /// with `b` (`Swap`), and anything calling `.reverse(` just reverses.
+    fn classify_method_kind(method_body: &str) -> Option<MethodKind> {
+        if method_body.contains(".reverse(") {
+            Some(MethodKind::Reverse)
+        } else if Regex::new(r"splice\(\s*0\s*,").ok()?.is_match(method_body) {
+            Some(MethodKind::Slice)
+        } else if method_body.contains(".splice(") {
+            Some(MethodKind::Splice)
+        } else if method_body.contains("[0]") && method_body.contains('=') {
+            Some(MethodKind::Swap)
+        } else {
+            None
+        }
     }
 
     fn find_signature_function_name(&self, js_content: &str) -> Result<String> {
@@ -198,17 +615,21 @@ impl SignatureDecrypter {
             return Ok("dummyObject".to_string());
         }
 
-        // Look for the transform object referenced in the signature function
+        // Look for the transform object referenced in the signature function.
+        // The naive "first identifier followed by a dot" match tends to land
+        // on the function's own parameter doing `a.split(...)` rather than
+        // the real transform object, so require the shape the transform
+        // calls actually take: `Obj.method(<same param>, ...)`.
         let patterns = [
-            format!(r#"{}=function\([^)]*\)\{{[^}}]*?([a-zA-Z_\$][\w\$]*)\."#, regex::escape(sig_func_name)),
-            format!(r#"function\s+{}\([^)]*\)\{{[^}}]*?([a-zA-Z_\$][\w\$]*)\."#, regex::escape(sig_func_name)),
-            format!(r#"{}:\s*function\([^)]*\)\{{[^}}]*?([a-zA-Z_\$][\w\$]*)\."#, regex::escape(sig_func_name)),
+            format!(r#"{}=function\(\s*(?P<arg>[a-zA-Z0-9_$]+)\s*\)\{{[^}}]*?(?P<obj>[a-zA-Z_\$][\w\$]*)\.\w+\(\s*(?P=arg)\s*,"#, regex::escape(sig_func_name)),
+            format!(r#"function\s+{}\(\s*(?P<arg>[a-zA-Z0-9_$]+)\s*\)\{{[^}}]*?(?P<obj>[a-zA-Z_\$][\w\$]*)\.\w+\(\s*(?P=arg)\s*,"#, regex::escape(sig_func_name)),
+            format!(r#"{}:\s*function\(\s*(?P<arg>[a-zA-Z0-9_$]+)\s*\)\{{[^}}]*?(?P<obj>[a-zA-Z_\$][\w\$]*)\.\w+\(\s*(?P=arg)\s*,"#, regex::escape(sig_func_name)),
         ];
 
         for pattern in &patterns {
             if let Ok(re) = Regex::new(pattern) {
                 if let Some(captures) = re.captures(js_content) {
-                    if let Some(obj_name) = captures.get(1) {
+                    if let Some(obj_name) = captures.name("obj") {
                         let name = obj_name.as_str().to_string();
                         tracing::debug!("Found transform object: {}", name);
                         return Ok(name);
@@ -221,101 +642,27 @@ impl SignatureDecrypter {
         Ok("dummyObject".to_string())
     }
 
-    fn extract_transform_operations(
-        &self,
-        js_content: &str,
-        transform_obj_name: &str,
-    ) -> Result<Vec<TransformOp>> {
-        let mut operations = Vec::new();
-
-        // If using dummy objects, skip complex parsing and use simple fallback
-        if transform_obj_name == "dummyObject" {
-            tracing::debug!("Using fallback transform operations");
-            // Common YouTube signature transformations based on yt-dlp observations
-            operations.push(TransformOp::Reverse);
-            operations.push(TransformOp::Splice(1));
-            operations.push(TransformOp::Swap(39));
-            return Ok(operations);
-        }
+}
 
-        // Look for the transform object definition with multiple patterns
-        let obj_patterns = [
-            format!(r#"var\s+{}\s*=\s*\{{([^}}]+)\}}"#, regex::escape(transform_obj_name)),
-            format!(r#"{}\s*=\s*\{{([^}}]+)\}}"#, regex::escape(transform_obj_name)),
-            format!(r#"const\s+{}\s*=\s*\{{([^}}]+)\}}"#, regex::escape(transform_obj_name)),
-            format!(r#"let\s+{}\s*=\s*\{{([^}}]+)\}}"#, regex::escape(transform_obj_name)),
-        ];
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        for obj_pattern in &obj_patterns {
-            if let Ok(re) = Regex::new(obj_pattern) {
-                if let Some(captures) = re.captures(js_content) {
-                    if let Some(obj_body) = captures.get(1) {
-                        // Parse the object methods
-                        let method_re =
-                            Regex::new(r#"([a-zA-Z_\$][\w\$]*):function\([^)]*\)\{([^}]+)\}"#)?;
-
-                        for method_match in method_re.captures_iter(obj_body.as_str()) {
-                            if let (Some(_method_name), Some(method_body)) =
-                                (method_match.get(1), method_match.get(2))
-                            {
-                                if let Ok(op) = self.parse_transform_method(method_body.as_str()) {
-                                    operations.push(op);
-                                }
-                            }
-                        }
-                        break;
-                    }
-                }
-            }
-        }
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_decrypt_many_matches_serial_decryption() {
+        let js = r#"
+        var sig = function(a) { a = a.split(""); a.reverse(); return a.join(""); };
+        "#;
 
-        if operations.is_empty() {
-            // Fallback: assume common operations based on yt-dlp patterns
-            tracing::debug!("No operations found, using common fallback operations");
-            operations.push(TransformOp::Reverse);
-            operations.push(TransformOp::Splice(1));
-            operations.push(TransformOp::Swap(39));
-        }
+        let mut pooled = SignatureDecrypter::new();
+        pooled.init_js_interpreter(js.to_string(), None).unwrap();
 
-        tracing::debug!("Extracted {} transform operations", operations.len());
-        Ok(operations)
-    }
+        let sigs = vec!["abcdef".to_string(), "uvwxyz".to_string()];
+        let results = pooled.decrypt_many(&sigs, js);
 
-    fn parse_transform_method(&self, method_body: &str) -> Result<TransformOp> {
-        if method_body.contains("reverse") {
-            Ok(TransformOp::Reverse)
-        } else if method_body.contains("splice") {
-            // Try to extract splice index
-            let splice_re = Regex::new(r#"splice\(\s*(\d+)\s*,\s*1\s*\)"#)?;
-            if let Some(captures) = splice_re.captures(method_body) {
-                if let Some(index_str) = captures.get(1) {
-                    if let Ok(index) = index_str.as_str().parse::<usize>() {
-                        return Ok(TransformOp::Splice(index));
-                    }
-                }
-            }
-            Ok(TransformOp::Splice(0))
-        } else if method_body.contains("swap") || method_body.contains("=") {
-            // Try to extract swap index
-            let swap_re = Regex::new(r#"\[0\]\s*=\s*[a-zA-Z_\$][a-zA-Z_0-9]*\[(\d+)\]"#)?;
-            if let Some(captures) = swap_re.captures(method_body) {
-                if let Some(index_str) = captures.get(1) {
-                    if let Ok(index) = index_str.as_str().parse::<usize>() {
-                        return Ok(TransformOp::Swap(index));
-                    }
-                }
-            }
-            Ok(TransformOp::Swap(1))
-        } else {
-            // Default to reverse if we can't determine the operation
-            Ok(TransformOp::Reverse)
-        }
+        assert_eq!(results[0].as_ref().unwrap(), "fedcba");
+        assert_eq!(results[1].as_ref().unwrap(), "zyxwvu");
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
     fn test_signature_decryption_basic() {
@@ -339,6 +686,39 @@ mod tests {
         assert!(result.is_err() || result.is_ok());
     }
 
+    #[test]
+    fn test_nsig_function_source_indexed_array_form() {
+        let js = r#"
+        var NFUNC=[otherFn,realNfunc,thirdFn];
+        a.D&&(b=a.get("n"))&&(b=null)&&(d=NFUNC[1](c));
+        realNfunc=function(a){var b=a.split("");if(b.length){b.reverse()}return b.join("")};
+        "#;
+
+        let (name, source) = SignatureDecrypter::find_nsig_function_source(js)
+            .expect("should locate indexed-array n-sig function");
+        assert_eq!(name, "realNfunc");
+
+        let interpreter = JSInterpreter::new(source).unwrap();
+        let result = interpreter.decrypt_signature(&name, "abcdef", None).unwrap();
+        assert_eq!(result, "fedcba");
+    }
+
+    #[test]
+    fn test_nsig_function_source_direct_name_form() {
+        let js = r#"
+        &&(b="n+"[x],c=a.get(b))&&(c=directNfunc(d));
+        directNfunc=function(a){var b=a.split("");if(b.length>1){b.splice(1,1)}return b.join("")};
+        "#;
+
+        let (name, source) = SignatureDecrypter::find_nsig_function_source(js)
+            .expect("should locate direct-name n-sig function");
+        assert_eq!(name, "directNfunc");
+
+        let interpreter = JSInterpreter::new(source).unwrap();
+        let result = interpreter.decrypt_signature(&name, "abcdef", None).unwrap();
+        assert_eq!(result, "acdef");
+    }
+
     #[test]
     fn test_transform_operations() {
         let mut decrypter = SignatureDecrypter::new();
@@ -358,4 +738,25 @@ mod tests {
         chars.swap(0, 2);
         assert_eq!(chars.iter().collect::<String>(), "cdfba");
     }
+
+    #[test]
+    fn test_decrypt_signature_derives_ordered_plan_with_slice_and_swap() {
+        let mut decrypter = SignatureDecrypter::new();
+
+        let js = r#"
+        Nq=function(a){a=a.split("");Sb.aa(a,3);Sb.bb(a,2);Sb.aa(a,1);return a.join("")};
+        var Sb={aa:function(a,b){a.splice(0,b)},bb:function(a,b){var c=a[0];a[0]=a[b%a.length];a[b%a.length]=c}};
+        "#;
+
+        // "abcdefgh" --drop 3--> "defgh" --swap(0,2)--> "fedgh" --drop 1--> "edgh"
+        let result = decrypter.decrypt_signature("abcdefgh", js).unwrap();
+        assert_eq!(result, "edgh");
+    }
+
+    #[test]
+    fn test_extract_signature_plan_fails_loudly_when_no_plan_found() {
+        let mut decrypter = SignatureDecrypter::new();
+        let err = decrypter.extract_signature_plan("not any recognizable player js", None).unwrap_err();
+        assert!(err.to_string().contains("could not locate"));
+    }
 }
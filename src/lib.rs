@@ -2,6 +2,8 @@ pub mod cli;
 pub mod config;
 pub mod core;
 pub mod extractors;
+pub mod postprocessor;
+pub mod server;
 pub mod utils;
 
 pub use core::{Downloader, ExtractorEngine, VideoFormat, VideoMetadata};
@@ -5,6 +5,8 @@ mod cli;
 mod config;
 mod core;
 mod extractors;
+mod postprocessor;
+mod server;
 mod utils;
 
 use cli::Cli;
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+/// Locates and invokes ffmpeg to mux separately-downloaded video-only and
+/// audio-only streams into a single container, modeled on yt-dlp's
+/// `FFmpegPostProcessor`.
+pub struct FfmpegPostProcessor {
+    ffmpeg_path: PathBuf,
+}
+
+impl FfmpegPostProcessor {
+    /// Resolve the ffmpeg binary: an explicit `--ffmpeg-location` (a
+    /// directory or the binary itself), falling back to a PATH search.
+    pub fn new(ffmpeg_location: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            ffmpeg_path: Self::locate_binary("ffmpeg", ffmpeg_location)?,
+        })
+    }
+
+    fn locate_binary(name: &str, location: Option<&str>) -> Result<PathBuf> {
+        if let Some(location) = location {
+            let path = Path::new(location);
+            let candidate = if path.is_dir() {
+                path.join(name)
+            } else {
+                path.to_path_buf()
+            };
+
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
+
+            anyhow::bail!("{} not found at --ffmpeg-location {}", name, location);
+        }
+
+        if let Some(path_var) = std::env::var_os("PATH") {
+            for dir in std::env::split_paths(&path_var) {
+                let candidate = dir.join(name);
+                if candidate.is_file() {
+                    return Ok(candidate);
+                }
+            }
+        }
+
+        anyhow::bail!(
+            "Could not find {} on PATH; pass --ffmpeg-location to specify it",
+            name
+        )
+    }
+
+    /// Mux a video-only and audio-only file into `output` with `-c copy`
+    /// (no re-encoding), then remove the intermediate files.
+    pub async fn mux(&self, video_path: &Path, audio_path: &Path, output: &Path) -> Result<()> {
+        info!(
+            "Muxing {} + {} -> {}",
+            video_path.display(),
+            audio_path.display(),
+            output.display()
+        );
+
+        let status = Command::new(&self.ffmpeg_path)
+            .arg("-y")
+            .arg("-i")
+            .arg(video_path)
+            .arg("-i")
+            .arg(audio_path)
+            .arg("-c")
+            .arg("copy")
+            .arg(output)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await?;
+
+        if !status.success() {
+            anyhow::bail!("ffmpeg exited with status {}", status);
+        }
+
+        let cleanup = |path: &Path| {
+            if let Err(e) = std::fs::remove_file(path) {
+                warn!("Failed to remove intermediate file {}: {}", path.display(), e);
+            }
+        };
+
+        cleanup(video_path);
+        cleanup(audio_path);
+
+        Ok(())
+    }
+}
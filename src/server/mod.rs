@@ -0,0 +1,243 @@
+//! Opt-in TCP daemon exposing `SignatureDecrypter` to other processes, so an
+//! Invidious-style frontend can batch-decrypt YouTube signatures without
+//! shelling out to this binary (or re-fetching/re-parsing the player JS) on
+//! every request. The player JS and its warmed QuickJS contexts stay
+//! resident in memory across requests until a `ForceUpdate` is received.
+//!
+//! Wire format: each request is a single leading opcode byte followed by
+//! zero or more length-prefixed (`u32` big-endian length, then that many
+//! UTF-8 bytes) string arguments. Each response is a single status byte
+//! (`0` = ok, `1` = error) followed by one length-prefixed UTF-8 string
+//! holding the result, or the error message.
+
+use anyhow::{anyhow, Result};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+
+use crate::extractors::youtube_signature::SignatureDecrypter;
+
+/// Default bind address for `yt-dlp-ng --serve`.
+pub const DEFAULT_ADDR: &str = "127.0.0.1:12999";
+
+/// Largest length-prefixed string argument `read_string_arg` will allocate
+/// for. Every real argument (a signature, an n-sig, a player URL) is well
+/// under a few KB; this just bounds how much a malicious/misbehaving client
+/// can make the daemon allocate from a 4-byte length prefix alone.
+const MAX_STRING_ARG_LEN: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Opcode {
+    /// Re-fetch the player JS at the given URL and reload it.
+    ForceUpdate,
+    /// Decrypt a classic signature against the resident player JS.
+    DecryptSignature,
+    /// Decrypt an n-sig (throttling parameter) against the resident player JS.
+    DecryptNSig,
+    /// Return the resident player's `signatureTimestamp`, if known.
+    GetSignatureTimestamp,
+    /// Return a short human-readable summary of the resident player.
+    PlayerStatus,
+}
+
+impl Opcode {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Opcode::ForceUpdate),
+            1 => Some(Opcode::DecryptSignature),
+            2 => Some(Opcode::DecryptNSig),
+            3 => Some(Opcode::GetSignatureTimestamp),
+            4 => Some(Opcode::PlayerStatus),
+            _ => None,
+        }
+    }
+}
+
+/// Everything the daemon keeps resident between requests: the decrypter
+/// (with its own warmed interpreter pool and player-info cache) plus the raw
+/// player JS text, since the pattern-based fallback path still needs the
+/// source to re-derive a transform plan on a cache miss.
+struct DaemonState {
+    decrypter: SignatureDecrypter,
+    player_js: Option<String>,
+    player_url: Option<String>,
+}
+
+impl DaemonState {
+    fn new() -> Self {
+        Self {
+            decrypter: SignatureDecrypter::new(),
+            player_js: None,
+            player_url: None,
+        }
+    }
+}
+
+/// Run the daemon, accepting connections until the process is killed.
+pub async fn run(addr: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    tracing::info!("Signature daemon listening on {}", addr);
+
+    let state = Arc::new(Mutex::new(DaemonState::new()));
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        tracing::debug!("Accepted connection from {}", peer);
+        let state = Arc::clone(&state);
+
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, state).await {
+                tracing::warn!("Connection from {} ended with error: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, state: Arc<Mutex<DaemonState>>) -> Result<()> {
+    loop {
+        let mut opcode_byte = [0u8; 1];
+        match stream.read_exact(&mut opcode_byte).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e.into()),
+        }
+
+        let Some(opcode) = Opcode::from_byte(opcode_byte[0]) else {
+            write_response(&mut stream, Err(anyhow!("unknown opcode {}", opcode_byte[0]))).await?;
+            continue;
+        };
+
+        let result = dispatch(opcode, &mut stream, &state).await;
+        write_response(&mut stream, result).await?;
+    }
+}
+
+async fn dispatch(
+    opcode: Opcode,
+    stream: &mut TcpStream,
+    state: &Arc<Mutex<DaemonState>>,
+) -> Result<String> {
+    match opcode {
+        Opcode::ForceUpdate => {
+            let player_url = read_string_arg(stream).await?;
+            let js_code = reqwest::get(&player_url).await?.text().await?;
+
+            let mut state = state.lock().await;
+            state.decrypter.init_js_interpreter(js_code.clone(), Some(&player_url))?;
+            state.player_js = Some(js_code);
+            state.player_url = Some(player_url.clone());
+
+            Ok(format!("loaded {}", player_url))
+        }
+        Opcode::DecryptSignature => {
+            let signature = read_string_arg(stream).await?;
+            let mut state = state.lock().await;
+            let js_code = state
+                .player_js
+                .clone()
+                .ok_or_else(|| anyhow!("no player loaded; send ForceUpdate first"))?;
+            state.decrypter.decrypt_signature(&signature, &js_code)
+        }
+        Opcode::DecryptNSig => {
+            let nsig = read_string_arg(stream).await?;
+            let mut state = state.lock().await;
+            let js_code = state
+                .player_js
+                .clone()
+                .ok_or_else(|| anyhow!("no player loaded; send ForceUpdate first"))?;
+            state.decrypter.decrypt_nsig(&nsig, &js_code)
+        }
+        Opcode::GetSignatureTimestamp => {
+            let state = state.lock().await;
+            state
+                .decrypter
+                .current_player_info()
+                .and_then(|info| info.signature_timestamp)
+                .map(|sts| sts.to_string())
+                .ok_or_else(|| anyhow!("no signatureTimestamp known for the resident player"))
+        }
+        Opcode::PlayerStatus => {
+            let state = state.lock().await;
+            match &state.player_url {
+                Some(url) => Ok(format!("loaded: {}", url)),
+                None => Ok("no player loaded".to_string()),
+            }
+        }
+    }
+}
+
+async fn read_string_arg(stream: &mut TcpStream) -> Result<String> {
+    let mut len_bytes = [0u8; 4];
+    stream.read_exact(&mut len_bytes).await?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+    if len > MAX_STRING_ARG_LEN {
+        return Err(anyhow!(
+            "string argument length {} exceeds maximum of {} bytes",
+            len,
+            MAX_STRING_ARG_LEN
+        ));
+    }
+
+    let mut buf = vec![0u8; len];
+    stream.read_exact(&mut buf).await?;
+    Ok(String::from_utf8(buf)?)
+}
+
+async fn write_response(stream: &mut TcpStream, result: Result<String>) -> Result<()> {
+    let (status, payload) = match result {
+        Ok(payload) => (0u8, payload),
+        Err(e) => (1u8, e.to_string()),
+    };
+
+    let payload = payload.into_bytes();
+    stream.write_all(&[status]).await?;
+    stream.write_all(&(payload.len() as u32).to_be_bytes()).await?;
+    stream.write_all(&payload).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opcode_from_byte() {
+        assert_eq!(Opcode::from_byte(0), Some(Opcode::ForceUpdate));
+        assert_eq!(Opcode::from_byte(1), Some(Opcode::DecryptSignature));
+        assert_eq!(Opcode::from_byte(2), Some(Opcode::DecryptNSig));
+        assert_eq!(Opcode::from_byte(3), Some(Opcode::GetSignatureTimestamp));
+        assert_eq!(Opcode::from_byte(4), Some(Opcode::PlayerStatus));
+        assert_eq!(Opcode::from_byte(5), None);
+    }
+
+    #[tokio::test]
+    async fn test_player_status_round_trip_before_any_player_loaded() {
+        let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        let listener = TcpListener::bind(addr).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(DaemonState::new()));
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let _ = handle_connection(stream, state).await;
+        });
+
+        let mut client = TcpStream::connect(local_addr).await.unwrap();
+        client.write_all(&[4]).await.unwrap();
+
+        let mut status = [0u8; 1];
+        client.read_exact(&mut status).await.unwrap();
+        assert_eq!(status[0], 0);
+
+        let mut len_bytes = [0u8; 4];
+        client.read_exact(&mut len_bytes).await.unwrap();
+        let len = u32::from_be_bytes(len_bytes) as usize;
+
+        let mut payload = vec![0u8; len];
+        client.read_exact(&mut payload).await.unwrap();
+        assert_eq!(String::from_utf8(payload).unwrap(), "no player loaded");
+    }
+}
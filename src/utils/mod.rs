@@ -1,3 +1,4 @@
+use regex::Regex;
 use std::path::PathBuf;
 
 pub fn sanitize_filename(filename: &str) -> String {
@@ -13,7 +14,61 @@ pub fn sanitize_filename(filename: &str) -> String {
         .collect()
 }
 
-pub fn generate_output_filename(template: &str, metadata: &crate::core::VideoMetadata) -> PathBuf {
+/// A single `%(field)s`/`%(field)d` value resolved out of `VideoMetadata`.
+enum TemplateValue {
+    Str(String),
+    Num(i64),
+}
+
+/// Resolve one output-template field name against the available metadata.
+/// Returns `None` for fields that don't exist or whose `Option` is empty, in
+/// which case the caller substitutes the `NA` fallback.
+fn resolve_field(
+    field: &str,
+    metadata: &crate::core::VideoMetadata,
+    playlist_index: Option<usize>,
+    ext: &str,
+) -> Option<TemplateValue> {
+    match field {
+        "title" => Some(TemplateValue::Str(metadata.title.clone())),
+        "id" => Some(TemplateValue::Str(metadata.id.clone())),
+        "ext" => Some(TemplateValue::Str(ext.to_string())),
+        "uploader" => metadata.uploader.clone().map(TemplateValue::Str),
+        "upload_date" => metadata.upload_date.clone().map(TemplateValue::Str),
+        "duration" => metadata.duration.map(|d| TemplateValue::Num(d as i64)),
+        "view_count" => metadata.view_count.map(|v| TemplateValue::Num(v as i64)),
+        "like_count" => metadata.like_count.map(|v| TemplateValue::Num(v as i64)),
+        "playlist_index" => playlist_index.map(|i| TemplateValue::Num(i as i64)),
+        _ => None,
+    }
+}
+
+/// Render one resolved field according to its template type (`s` or `d`) and
+/// optional zero-padding width, e.g. `%(view_count)05d`.
+fn render_field(value: Option<TemplateValue>, kind: &str, width: Option<usize>) -> String {
+    match value {
+        None => "NA".to_string(),
+        Some(TemplateValue::Str(s)) => match kind {
+            "s" => sanitize_filename(&s),
+            _ => s,
+        },
+        Some(TemplateValue::Num(n)) => match width {
+            Some(width) => format!("{:0width$}", n, width = width),
+            None => n.to_string(),
+        },
+    }
+}
+
+/// Render an output filename from a `%(field)[width][type]` template, e.g.
+/// `%(uploader)s/%(upload_date)s - %(title)s [%(id)s].%(ext)s` or
+/// `%(playlist_index)03d - %(title)s.%(ext)s`. Fields with no value (e.g. a
+/// video with no `upload_date`) fall back to `NA`; string fields are run
+/// through `sanitize_filename`.
+pub fn generate_output_filename(
+    template: &str,
+    metadata: &crate::core::VideoMetadata,
+    playlist_index: Option<usize>,
+) -> PathBuf {
     // Get the best format for determining extension
     let best_format = metadata
         .formats
@@ -24,22 +79,44 @@ pub fn generate_output_filename(template: &str, metadata: &crate::core::VideoMet
 
     let ext = best_format.map(|f| f.ext.as_str()).unwrap_or("mp4");
 
-    // Simple template replacement
-    let filename = template
-        .replace("%(title)s", &sanitize_filename(&metadata.title))
-        .replace("%(id)s", &metadata.id)
-        .replace(
-            "%(uploader)s",
-            &metadata.uploader.as_deref().unwrap_or("Unknown"),
-        )
-        .replace("%(ext)s", ext);
-
-    PathBuf::from(filename)
+    let field_pattern = Regex::new(r"%\((\w+)\)(\d*)([sd])").expect("valid template regex");
+    let filename = field_pattern.replace_all(template, |caps: &regex::Captures| {
+        let field = &caps[1];
+        let width: Option<usize> = caps[2].parse().ok();
+        let kind = &caps[3];
+        let value = resolve_field(field, metadata, playlist_index, ext);
+        render_field(value, kind, width)
+    });
+
+    PathBuf::from(filename.into_owned())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::VideoMetadata;
+    use std::collections::HashMap;
+
+    fn sample_metadata() -> VideoMetadata {
+        VideoMetadata {
+            id: "abc123".to_string(),
+            title: "Test Video".to_string(),
+            description: None,
+            duration: Some(125),
+            uploader: Some("Test Channel".to_string()),
+            upload_date: None,
+            view_count: Some(42),
+            like_count: None,
+            category: None,
+            tags: Vec::new(),
+            formats: Vec::new(),
+            thumbnails: Vec::new(),
+            subtitles: HashMap::new(),
+            automatic_captions: HashMap::new(),
+            translation_languages: Vec::new(),
+            chapters: Vec::new(),
+        }
+    }
 
     #[test]
     fn test_sanitize_filename() {
@@ -47,4 +124,25 @@ mod tests {
         assert_eq!(sanitize_filename("test<>file"), "test__file");
         assert_eq!(sanitize_filename("normal_file.mp4"), "normal_file.mp4");
     }
+
+    #[test]
+    fn test_generate_output_filename_padding() {
+        let metadata = sample_metadata();
+        let filename = generate_output_filename("%(view_count)05d - %(title)s.%(ext)s", &metadata, None);
+        assert_eq!(filename, PathBuf::from("00042 - Test Video.mp4"));
+    }
+
+    #[test]
+    fn test_generate_output_filename_playlist_index() {
+        let metadata = sample_metadata();
+        let filename = generate_output_filename("%(playlist_index)03d - %(title)s.%(ext)s", &metadata, Some(7));
+        assert_eq!(filename, PathBuf::from("007 - Test Video.mp4"));
+    }
+
+    #[test]
+    fn test_generate_output_filename_missing_field_falls_back_to_na() {
+        let metadata = sample_metadata();
+        let filename = generate_output_filename("%(upload_date)s - %(title)s.%(ext)s", &metadata, None);
+        assert_eq!(filename, PathBuf::from("NA - Test Video.mp4"));
+    }
 }
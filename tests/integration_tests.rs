@@ -6,10 +6,10 @@ use yt_dlp_ng::extractors::YouTubeExtractor;
 
 #[tokio::test]
 async fn test_extractor_engine_initialization() -> Result<()> {
-    let mut engine = ExtractorEngine::new();
-    engine.register_extractor(Box::new(YouTubeExtractor::new()));
-    
-    // Test that extractor is registered
+    // YouTubeExtractor is registered by default; callers shouldn't need to
+    // register it manually just to get a usable engine.
+    let engine = ExtractorEngine::new();
+
     assert!(engine.extractors.len() > 0);
     Ok(())
 }
@@ -22,7 +22,9 @@ async fn test_youtube_extractor_suitable() -> Result<()> {
     assert!(extractor.suitable(&Url::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ")?));
     assert!(extractor.suitable(&Url::parse("https://youtu.be/dQw4w9WgXcQ")?));
     assert!(extractor.suitable(&Url::parse("https://m.youtube.com/watch?v=dQw4w9WgXcQ")?));
-    
+    assert!(extractor.suitable(&Url::parse("https://music.youtube.com/watch?v=dQw4w9WgXcQ")?));
+    assert!(extractor.suitable(&Url::parse("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ")?));
+
     // Test non-YouTube URLs
     assert!(!extractor.suitable(&Url::parse("https://vimeo.com/123456")?));
     assert!(!extractor.suitable(&Url::parse("https://example.com")?));
@@ -40,6 +42,14 @@ async fn test_youtube_video_id_extraction() -> Result<()> {
         ("https://youtu.be/dQw4w9WgXcQ", "dQw4w9WgXcQ"),
         ("https://m.youtube.com/watch?v=dQw4w9WgXcQ", "dQw4w9WgXcQ"),
         ("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=123", "dQw4w9WgXcQ"),
+        ("https://www.youtube.com/embed/dQw4w9WgXcQ", "dQw4w9WgXcQ"),
+        ("https://www.youtube.com/shorts/dQw4w9WgXcQ", "dQw4w9WgXcQ"),
+        ("https://www.youtube.com/live/dQw4w9WgXcQ", "dQw4w9WgXcQ"),
+        ("https://www.youtube.com/v/dQw4w9WgXcQ", "dQw4w9WgXcQ"),
+        ("https://www.youtube.com/watch_popup?v=dQw4w9WgXcQ", "dQw4w9WgXcQ"),
+        ("https://music.youtube.com/watch?v=dQw4w9WgXcQ", "dQw4w9WgXcQ"),
+        ("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ", "dQw4w9WgXcQ"),
+        ("https://www.youtube.com/#/watch?v=dQw4w9WgXcQ", "dQw4w9WgXcQ"),
     ];
     
     for (url_str, expected_id) in test_cases {
@@ -118,9 +128,14 @@ async fn test_video_metadata_creation() -> Result<()> {
         upload_date: Some("2024-01-01".to_string()),
         view_count: Some(1000),
         like_count: Some(50),
+        category: Some("Education".to_string()),
+        tags: vec!["test".to_string()],
         formats: vec![],
         thumbnails: vec![],
         subtitles: std::collections::HashMap::new(),
+        automatic_captions: std::collections::HashMap::new(),
+        translation_languages: Vec::new(),
+        chapters: Vec::new(),
     };
     
     assert_eq!(metadata.id, "test_video");
@@ -168,6 +183,8 @@ async fn test_output_filename_generation() -> Result<()> {
         upload_date: None,
         view_count: None,
         like_count: None,
+        category: None,
+        tags: Vec::new(),
         formats: vec![
             VideoFormat {
                 format_id: "18".to_string(),
@@ -186,12 +203,15 @@ async fn test_output_filename_generation() -> Result<()> {
         ],
         thumbnails: vec![],
         subtitles: std::collections::HashMap::new(),
+        automatic_captions: std::collections::HashMap::new(),
+        translation_languages: Vec::new(),
+        chapters: Vec::new(),
     };
     
-    let filename = generate_output_filename("%(title)s.%(ext)s", &metadata);
+    let filename = generate_output_filename("%(title)s.%(ext)s", &metadata, None);
     assert_eq!(filename, PathBuf::from("Test Video.mp4"));
     
-    let filename = generate_output_filename("%(uploader)s - %(title)s.%(ext)s", &metadata);
+    let filename = generate_output_filename("%(uploader)s - %(title)s.%(ext)s", &metadata, None);
     assert_eq!(filename, PathBuf::from("Test Channel - Test Video.mp4"));
     
     Ok(())